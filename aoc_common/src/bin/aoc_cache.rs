@@ -0,0 +1,108 @@
+/* file:    aoc_cache.rs
+ * author:  garnt
+ * date:    01/04/2025
+ * desc:    Manages the local puzzle input cache.
+ */
+
+use aoc_common::cache::{Answer, Cache, CachedDay};
+use aoc_common::input::{InputName, YEAR};
+use std::path::PathBuf;
+
+const USAGE: &str = "\
+usage: aoc_cache import <day> <file> [--name <input>] [--year <year>]
+       aoc_cache answer <day> <part> <answer> [--name <input>] [--year <year>]
+       aoc_cache list
+
+<input> is real (the default), example, or example<n>. the cache lives in
+$AOC_CACHE_DIR, $XDG_CACHE_HOME/aoc or ~/.cache/aoc, whichever is set first.";
+
+// prints the usage and an error, and exits
+fn fail(msg: &str) -> ! {
+    eprintln!("{}\n\n{}", msg, USAGE);
+    std::process::exit(1);
+}
+
+/// the entrypoint
+fn main() {
+    let cache: Cache = Cache::default_location()
+        .unwrap_or_else(|| fail("couldn't work out where the cache should be"));
+
+    // split the arguments into positional ones and --name/--year
+    let mut positional: Vec<String> = Vec::new();
+    let mut name: InputName = InputName::Real;
+    let mut year: u32 = YEAR;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| fail(&format!("{} needs a value", arg)))
+        };
+        match arg.as_str() {
+            "--name" => name = value().parse().unwrap_or_else(|e: String| fail(&e)),
+            "--year" => {
+                year = value()
+                    .parse()
+                    .unwrap_or_else(|_| fail("--year needs a number"))
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let parse_num = |arg: &str, what: &str| {
+        arg.parse::<u32>()
+            .unwrap_or_else(|_| fail(&format!("invalid {} '{}'", what, arg)))
+    };
+
+    let positional: Vec<&str> = positional.iter().map(String::as_str).collect();
+    match positional[..] {
+        ["import", day, file] => {
+            let day: u32 = parse_num(day, "day");
+            match cache.import(year, day, name, &PathBuf::from(file)) {
+                Ok(dest) => println!("imported {} as {}", file, dest.display()),
+                Err(e) => fail(&format!("couldn't import {} - {}", file, e)),
+            }
+        }
+        ["answer", day, part, answer] => {
+            let day: u32 = parse_num(day, "day");
+            let answer = Answer {
+                name,
+                part: parse_num(part, "part"),
+                answer: answer.to_string(),
+            };
+            let recorded: String = answer.to_string();
+            match cache.record_answer(year, day, answer) {
+                Ok(()) => println!("recorded {} for {} day {}", recorded, year, day),
+                Err(e) => fail(&format!("couldn't record answer - {}", e)),
+            }
+        }
+        ["list"] => {
+            let days: Vec<CachedDay> = cache
+                .list()
+                .unwrap_or_else(|e| fail(&format!("couldn't list the cache - {}", e)));
+            println!("cache: {}", cache.root().display());
+            if days.is_empty() {
+                println!("(empty)");
+            }
+            for cached in days {
+                let inputs: Vec<String> =
+                    cached.inputs.iter().map(|name| name.to_string()).collect();
+                println!(
+                    "{} day {:02}: inputs [{}]",
+                    cached.year,
+                    cached.day,
+                    inputs.join(", ")
+                );
+                for answer in cached.answers {
+                    println!(
+                        "    {} part {}: {}",
+                        answer.name, answer.part, answer.answer
+                    );
+                }
+            }
+        }
+        _ => fail("unknown command"),
+    }
+}
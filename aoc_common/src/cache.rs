@@ -0,0 +1,348 @@
+/* file:    cache.rs
+ * author:  garnt
+ * date:    01/04/2025
+ * desc:    Local store of puzzle inputs and known answers, laid out as
+ *          <root>/<year>/day_<dd>/, so inputs don't have to be checked in
+ *          next to each crate.
+ */
+
+use crate::input::InputName;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// the file known answers are kept in, inside each day's directory
+const ANSWERS_FILE: &str = "answers.txt";
+
+/// a known answer to one part of a puzzle, for one of its inputs
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Answer {
+    pub name: InputName,
+    pub part: u32,
+    pub answer: String,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.name, self.part, self.answer)
+    }
+}
+
+/// everything the cache holds for a single day
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedDay {
+    pub year: u32,
+    pub day: u32,
+    pub inputs: Vec<InputName>,
+    pub answers: Vec<Answer>,
+}
+
+/// the local input cache
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cache {
+    root: PathBuf,
+}
+
+impl Cache {
+    /// constructs a new Cache rooted at the provided directory
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    /// the cache in its usual place: $AOC_CACHE_DIR if it's set, otherwise
+    /// $XDG_CACHE_HOME/aoc, otherwise ~/.cache/aoc. returns None if none of
+    /// those can be worked out.
+    pub fn default_location() -> Option<Self> {
+        let env_dir = |var: &str| {
+            std::env::var_os(var)
+                .filter(|val| !val.is_empty())
+                .map(PathBuf::from)
+        };
+        env_dir("AOC_CACHE_DIR")
+            .or_else(|| env_dir("XDG_CACHE_HOME").map(|dir| dir.join("aoc")))
+            .or_else(|| env_dir("HOME").map(|dir| dir.join(".cache").join("aoc")))
+            .map(Self::new)
+    }
+
+    /// the directory the cache lives in
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// the directory a single day's files live in, e.g. 2024/day_07
+    pub fn day_dir(&self, year: u32, day: u32) -> PathBuf {
+        self.root
+            .join(year.to_string())
+            .join(format!("day_{:02}", day))
+    }
+
+    /// the path a named input is stored at
+    pub fn input_path(&self, year: u32, day: u32, name: InputName) -> PathBuf {
+        self.day_dir(year, day).join(name.file_name())
+    }
+
+    /// copies an input file into the cache, replacing any input already stored
+    /// under that name, and returns where it ended up
+    pub fn import(&self, year: u32, day: u32, name: InputName, from: &Path) -> io::Result<PathBuf> {
+        let dest: PathBuf = self.input_path(year, day, name);
+        std::fs::create_dir_all(self.day_dir(year, day))?;
+        std::fs::copy(from, &dest)?;
+        Ok(dest)
+    }
+
+    /// returns the known answers for a day, which are stored one per line as
+    /// "<input> <part> <answer>". blank lines and lines starting with '#' are
+    /// ignored.
+    pub fn answers(&self, year: u32, day: u32) -> io::Result<Vec<Answer>> {
+        let path: PathBuf = self.day_dir(year, day).join(ANSWERS_FILE);
+        let contents: String = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(idx, line)| {
+                let invalid = |msg: String| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{} line {}: {}", path.display(), idx + 1, msg),
+                    )
+                };
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let [name, part, answer] = fields[..] else {
+                    return Err(invalid(format!(
+                        "expected '<input> <part> <answer>', found '{}'",
+                        line
+                    )));
+                };
+                Ok(Answer {
+                    name: name.parse().map_err(invalid)?,
+                    part: part
+                        .parse()
+                        .map_err(|e| invalid(format!("invalid part '{}': {}", part, e)))?,
+                    answer: answer.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// returns the known answer to one part of a day, for one of its inputs
+    pub fn answer(
+        &self,
+        year: u32,
+        day: u32,
+        name: InputName,
+        part: u32,
+    ) -> io::Result<Option<String>> {
+        Ok(self
+            .answers(year, day)?
+            .into_iter()
+            .find(|answer| answer.name == name && answer.part == part)
+            .map(|answer| answer.answer))
+    }
+
+    /// records the answer to one part of a day, replacing any answer already
+    /// known for that input and part. answers are stored space-separated, so
+    /// one that's empty or contains whitespace is rejected.
+    pub fn record_answer(&self, year: u32, day: u32, answer: Answer) -> io::Result<()> {
+        if answer.answer.is_empty() || answer.answer.contains(char::is_whitespace) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "answer '{}' can't be empty or contain whitespace",
+                    answer.answer
+                ),
+            ));
+        }
+
+        let mut answers: Vec<Answer> = self.answers(year, day)?;
+        answers.retain(|known| (known.name, known.part) != (answer.name, answer.part));
+        answers.push(answer);
+        answers.sort_by_key(|answer| (answer.name.file_name(), answer.part));
+
+        let lines: Vec<String> = answers.iter().map(|answer| answer.to_string()).collect();
+        std::fs::create_dir_all(self.day_dir(year, day))?;
+        std::fs::write(
+            self.day_dir(year, day).join(ANSWERS_FILE),
+            format!("{}\n", lines.join("\n")),
+        )
+    }
+
+    /// lists everything in the cache, ordered by year and day
+    pub fn list(&self) -> io::Result<Vec<CachedDay>> {
+        // reads a directory's entry names, treating a missing one as empty
+        let entry_names = |dir: &Path| -> io::Result<Vec<String>> {
+            match std::fs::read_dir(dir) {
+                Ok(entries) => entries
+                    .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                    .collect(),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+                Err(e) => Err(e),
+            }
+        };
+
+        let mut days: Vec<CachedDay> = Vec::new();
+        for year_name in entry_names(&self.root)? {
+            let Ok(year) = year_name.parse::<u32>() else {
+                continue;
+            };
+            for day_name in entry_names(&self.root.join(&year_name))? {
+                let Some(day) = day_name
+                    .strip_prefix("day_")
+                    .and_then(|day| day.parse::<u32>().ok())
+                else {
+                    continue;
+                };
+
+                // anything named like an input counts as one
+                let mut inputs: Vec<InputName> = entry_names(&self.day_dir(year, day))?
+                    .iter()
+                    .filter_map(|file_name| input_name_for_file(file_name))
+                    .collect();
+                inputs.sort_by_key(|name| name.file_name());
+                days.push(CachedDay {
+                    year,
+                    day,
+                    inputs,
+                    answers: self.answers(year, day)?,
+                });
+            }
+        }
+
+        days.sort_by_key(|cached| (cached.year, cached.day));
+        Ok(days)
+    }
+}
+
+/// works out which input a file holds from its name, the reverse of
+/// InputName::file_name
+fn input_name_for_file(file_name: &str) -> Option<InputName> {
+    match file_name {
+        "input.txt" => Some(InputName::Real),
+        "test-input.txt" => Some(InputName::Example(1)),
+        _ => file_name
+            .strip_prefix("test-input-")?
+            .strip_suffix(".txt")?
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n > 1)
+            .map(InputName::Example),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// returns an empty directory for a single test to use as its cache
+    pub(crate) fn scratch_dir(test_name: &str) -> PathBuf {
+        let dir: PathBuf =
+            std::env::temp_dir().join(format!("aoc_common_{}_{}", test_name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn recorded_answers_can_be_read_back() {
+        let root: PathBuf = scratch_dir("recorded_answers");
+        let cache: Cache = Cache::new(&root);
+        let answer = |part: u32, answer: &str| Answer {
+            name: InputName::Example(2),
+            part,
+            answer: answer.to_string(),
+        };
+
+        cache.record_answer(2024, 17, answer(1, "4,6,3,5")).unwrap();
+        cache.record_answer(2024, 17, answer(2, "117440")).unwrap();
+        for bad in ["", "4 6 3 5", "117440\n"] {
+            let err: io::Error = cache.record_answer(2024, 17, answer(2, bad)).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+        assert_eq!(
+            cache.answers(2024, 17).unwrap(),
+            [answer(1, "4,6,3,5"), answer(2, "117440")]
+        );
+        assert_eq!(
+            cache.answer(2024, 17, InputName::Example(2), 1).unwrap(),
+            Some(String::from("4,6,3,5"))
+        );
+        assert_eq!(cache.answer(2024, 17, InputName::Real, 1).unwrap(), None);
+        assert_eq!(cache.answer(2024, 18, InputName::Real, 1).unwrap(), None);
+
+        // a malformed answers file is an error naming the line, not no answer
+        std::fs::write(
+            cache.day_dir(2024, 17).join(ANSWERS_FILE),
+            "# notes\nreal 1\n",
+        )
+        .unwrap();
+        let err: io::Error = cache.answer(2024, 17, InputName::Real, 1).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().contains("line 2: expected"), "{}", err);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn imported_inputs_are_listed() {
+        let root: PathBuf = scratch_dir("imported_inputs");
+        let cache: Cache = Cache::new(root.join("cache"));
+        let from: PathBuf = root.join("downloaded.txt");
+        std::fs::write(&from, "1 2\n").unwrap();
+        assert_eq!(cache.list().unwrap(), []);
+
+        let dest: PathBuf = cache.import(2024, 7, InputName::Real, &from).unwrap();
+        assert_eq!(dest, cache.day_dir(2024, 7).join("input.txt"));
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "1 2\n");
+        cache.import(2024, 7, InputName::Example(2), &from).unwrap();
+        cache.import(2023, 1, InputName::Example(1), &from).unwrap();
+
+        // importing again replaces the old copy
+        std::fs::write(&from, "3 4\n").unwrap();
+        cache.import(2024, 7, InputName::Real, &from).unwrap();
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "3 4\n");
+
+        // anything that isn't named like a year, day or input is skipped
+        std::fs::write(cache.day_dir(2024, 7).join("notes.txt"), "").unwrap();
+        std::fs::create_dir_all(root.join("cache").join("scratch")).unwrap();
+        std::fs::create_dir_all(root.join("cache").join("2024").join("day_xx")).unwrap();
+        cache
+            .record_answer(
+                2024,
+                7,
+                Answer {
+                    name: InputName::Real,
+                    part: 1,
+                    answer: String::from("3749"),
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            cache.list().unwrap(),
+            [
+                CachedDay {
+                    year: 2023,
+                    day: 1,
+                    inputs: vec![InputName::Example(1)],
+                    answers: Vec::new(),
+                },
+                CachedDay {
+                    year: 2024,
+                    day: 7,
+                    inputs: vec![InputName::Real, InputName::Example(2)],
+                    answers: cache.answers(2024, 7).unwrap(),
+                },
+            ]
+        );
+        assert_eq!(cache.answers(2024, 7).unwrap().len(), 1);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
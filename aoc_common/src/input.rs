@@ -0,0 +1,711 @@
+/* file:    input.rs
+ * author:  garnt
+ * date:    01/04/2025
+ * desc:    Finds and loads a day's puzzle input, by name, path or stdin.
+ */
+
+use crate::cache::Cache;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// the year every day's inputs belong to
+pub const YEAR: u32 = 2024;
+
+/// one of a day's named inputs
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InputName {
+    /// the real puzzle input, input.txt
+    Real,
+    /// one of the puzzle's worked examples, numbered from 1. the first is
+    /// test-input.txt, and the rest are test-input-<n>.txt.
+    Example(usize),
+}
+
+impl InputName {
+    /// the name of the file this input is stored in
+    pub fn file_name(&self) -> String {
+        match self {
+            InputName::Real => String::from("input.txt"),
+            InputName::Example(1) => String::from("test-input.txt"),
+            InputName::Example(n) => format!("test-input-{}.txt", n),
+        }
+    }
+}
+
+impl FromStr for InputName {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "real" => Ok(InputName::Real),
+            "example" => Ok(InputName::Example(1)),
+            _ => s
+                .strip_prefix("example")
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|n| *n > 0)
+                .map(InputName::Example)
+                .ok_or_else(|| {
+                    format!(
+                        "unknown input '{}', expected real, example or example<n>",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+impl fmt::Display for InputName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputName::Real => write!(f, "real"),
+            InputName::Example(1) => write!(f, "example"),
+            InputName::Example(n) => write!(f, "example{}", n),
+        }
+    }
+}
+
+/// where to read an input from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    Named(InputName),
+    Stdin,
+    Path(PathBuf),
+}
+
+impl InputSource {
+    /// parses a command line argument: "-" is stdin, a known input name like
+    /// "example" is that input, and anything else is a path
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            InputSource::Stdin
+        } else if let Ok(name) = arg.parse::<InputName>() {
+            InputSource::Named(name)
+        } else {
+            InputSource::Path(PathBuf::from(arg))
+        }
+    }
+
+    /// reads "--input <source>" from the program's arguments, for days that
+    /// don't otherwise take any. exits with an error if there are any other
+    /// arguments, or --input has no value.
+    pub fn from_env_args() -> Self {
+        Args::from_env().finish()
+    }
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Named(InputName::Real)
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Named(name) => write!(f, "{} input", name),
+            InputSource::Stdin => write!(f, "stdin"),
+            InputSource::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// an error in the program's arguments
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgError {
+    /// a flag that needs a value didn't get one
+    MissingValue(String),
+    /// a flag's value couldn't be parsed
+    InvalidValue {
+        flag: String,
+        value: String,
+        reason: String,
+    },
+    /// an argument the day doesn't take
+    Unknown(String),
+}
+
+impl ArgError {
+    /// prints the error and exits, the same way load_or_exit does
+    pub fn exit(&self) -> ! {
+        eprintln!("invalid arguments - {}", self);
+        std::process::exit(1);
+    }
+}
+
+impl fmt::Display for ArgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArgError::MissingValue(flag) => write!(f, "{} needs a value", flag),
+            ArgError::InvalidValue {
+                flag,
+                value,
+                reason,
+            } => write!(f, "invalid value '{}' for {} - {}", value, flag, reason),
+            ArgError::Unknown(arg) => write!(f, "unknown argument '{}'", arg),
+        }
+    }
+}
+
+impl std::error::Error for ArgError {}
+
+/// a day's command line arguments. "--input <source>" is picked out while
+/// iterating, and every other argument is left to the day. a flag's value is
+/// never taken from an argument starting with "--", so a missing value can't
+/// swallow the next flag.
+#[derive(Clone, Debug)]
+pub struct Args {
+    args: std::iter::Peekable<std::vec::IntoIter<String>>,
+    source: InputSource,
+}
+
+impl Args {
+    /// constructs a new Args from a list of arguments, not including the
+    /// program's name
+    pub fn new(args: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            args: args
+                .into_iter()
+                .collect::<Vec<String>>()
+                .into_iter()
+                .peekable(),
+            source: InputSource::default(),
+        }
+    }
+
+    /// the program's own arguments
+    pub fn from_env() -> Self {
+        Self::new(std::env::args().skip(1))
+    }
+
+    /// where to read the input from, as set by the last --input seen so far
+    pub fn source(&self) -> &InputSource {
+        &self.source
+    }
+
+    /// returns the next argument that isn't part of an --input
+    pub fn try_next(&mut self) -> Result<Option<String>, ArgError> {
+        while let Some(arg) = self.args.next() {
+            if arg != "--input" {
+                return Ok(Some(arg));
+            }
+            self.source = self.try_value_with(&arg, |value| {
+                Ok::<InputSource, String>(InputSource::from_arg(value))
+            })?;
+        }
+
+        Ok(None)
+    }
+
+    /// parses the value following a flag with the provided function
+    pub fn try_value_with<T, E, F>(&mut self, flag: &str, parse: F) -> Result<T, ArgError>
+    where
+        E: fmt::Display,
+        F: FnOnce(&str) -> Result<T, E>,
+    {
+        self.try_optional_value_with(flag, parse)?
+            .ok_or_else(|| ArgError::MissingValue(flag.to_string()))
+    }
+
+    /// parses the value following a flag with the provided function, if it
+    /// has one
+    pub fn try_optional_value_with<T, E, F>(
+        &mut self,
+        flag: &str,
+        parse: F,
+    ) -> Result<Option<T>, ArgError>
+    where
+        E: fmt::Display,
+        F: FnOnce(&str) -> Result<T, E>,
+    {
+        let Some(value) = self.args.next_if(|next| !next.starts_with("--")) else {
+            return Ok(None);
+        };
+        match parse(&value) {
+            Ok(parsed) => Ok(Some(parsed)),
+            Err(e) => Err(ArgError::InvalidValue {
+                flag: flag.to_string(),
+                value,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    /// parses the value following a flag, exiting if it's missing or invalid
+    pub fn value<T>(&mut self, flag: &str) -> T
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.value_with(flag, |value| value.parse::<T>())
+    }
+
+    /// parses the value following a flag with the provided function, exiting
+    /// if it's missing or invalid
+    pub fn value_with<T, E, F>(&mut self, flag: &str, parse: F) -> T
+    where
+        E: fmt::Display,
+        F: FnOnce(&str) -> Result<T, E>,
+    {
+        self.try_value_with(flag, parse)
+            .unwrap_or_else(|e| e.exit())
+    }
+
+    /// parses the value following a flag if it has one, exiting if it's
+    /// invalid
+    pub fn optional_value<T>(&mut self, flag: &str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.try_optional_value_with(flag, |value| value.parse::<T>())
+            .unwrap_or_else(|e| e.exit())
+    }
+
+    /// checks there are no arguments left other than --input, and returns
+    /// where to read the input from
+    pub fn try_finish(mut self) -> Result<InputSource, ArgError> {
+        match self.try_next()? {
+            Some(arg) => Err(ArgError::Unknown(arg)),
+            None => Ok(self.source),
+        }
+    }
+
+    /// checks there are no arguments left other than --input, exiting if there
+    /// are, and returns where to read the input from
+    pub fn finish(self) -> InputSource {
+        self.try_finish().unwrap_or_else(|e| e.exit())
+    }
+}
+
+impl Iterator for Args {
+    type Item = String;
+
+    /// returns the next argument that isn't part of an --input, exiting if
+    /// --input has no value
+    fn next(&mut self) -> Option<String> {
+        self.try_next().unwrap_or_else(|e| e.exit())
+    }
+}
+
+/// an error encountered while loading an input
+#[derive(Debug)]
+pub enum InputError {
+    /// a named input couldn't be found in any of the places it could be
+    Missing {
+        day: u32,
+        name: InputName,
+        tried: Vec<PathBuf>,
+    },
+    /// the input was found, but couldn't be read
+    Io {
+        source: InputSource,
+        err: std::io::Error,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing { day, name, tried } => {
+                let tried: Vec<String> = tried
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                write!(
+                    f,
+                    "no {} input for day {} - looked for {} (import one with aoc_cache)",
+                    name,
+                    day,
+                    tried.join(", ")
+                )
+            }
+            InputError::Io { source, err } => write!(f, "couldn't read {} - {}", source, err),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputError::Missing { .. } => None,
+            InputError::Io { err, .. } => Some(err),
+        }
+    }
+}
+
+/// how an answer compares to the one recorded in the cache
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnswerCheck {
+    /// there's no recorded answer to compare against
+    Unknown,
+    /// the answer matches the recorded one
+    Correct,
+    /// the answer differs from the recorded one
+    Wrong { expected: String },
+}
+
+/// finds the inputs for a single day
+#[derive(Clone, Debug)]
+pub struct DayInputs {
+    day: u32,
+    /// the directory the day's input files live in
+    dir: PathBuf,
+    /// the local input cache, which is checked before dir
+    cache: Option<Cache>,
+    /// inputs compiled into the binary, used if their file can't be found
+    embedded: Vec<(InputName, &'static str)>,
+}
+
+impl DayInputs {
+    /// constructs a new DayInputs that looks for files in the local input
+    /// cache, then in dir, which is usually the day's env!("CARGO_MANIFEST_DIR")
+    pub fn new(day: u32, dir: impl AsRef<Path>) -> Self {
+        Self {
+            day,
+            dir: dir.as_ref().to_path_buf(),
+            cache: Cache::default_location(),
+            embedded: Vec::new(),
+        }
+    }
+
+    /// looks in the provided cache instead of the default one, or in no cache
+    /// at all if it's None
+    pub fn with_cache(mut self, cache: Option<Cache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// compiles an input into the binary, e.g. with include_str!, so it's
+    /// available even if its file isn't
+    pub fn embed(mut self, name: InputName, contents: &'static str) -> Self {
+        self.embedded
+            .retain(|(embedded_name, _)| *embedded_name != name);
+        self.embedded.push((name, contents));
+        self
+    }
+
+    /// returns an embedded input, if there is one
+    pub fn embedded(&self, name: InputName) -> Option<&'static str> {
+        self.embedded
+            .iter()
+            .find(|(embedded_name, _)| *embedded_name == name)
+            .map(|(_, contents)| *contents)
+    }
+
+    /// the paths a named input might be found at, in the order they're tried
+    pub fn candidate_paths(&self, name: InputName) -> Vec<PathBuf> {
+        let cached: Option<PathBuf> = self
+            .cache
+            .as_ref()
+            .map(|cache| cache.input_path(YEAR, self.day, name));
+        cached
+            .into_iter()
+            .chain(std::iter::once(self.dir.join(name.file_name())))
+            .collect()
+    }
+
+    /// compares an answer to one part of the puzzle against the one recorded
+    /// in the cache. only named inputs can have a known answer.
+    pub fn check_answer(
+        &self,
+        source: &InputSource,
+        part: u32,
+        answer: &impl fmt::Display,
+    ) -> std::io::Result<AnswerCheck> {
+        let (InputSource::Named(name), Some(cache)) = (source, &self.cache) else {
+            return Ok(AnswerCheck::Unknown);
+        };
+        let answer: String = answer.to_string();
+        Ok(match cache.answer(YEAR, self.day, *name, part)? {
+            None => AnswerCheck::Unknown,
+            Some(expected) if expected == answer => AnswerCheck::Correct,
+            Some(expected) => AnswerCheck::Wrong { expected },
+        })
+    }
+
+    /// checks an answer against the known one, printing the outcome to stderr
+    /// so it doesn't get mixed up with the day's own output
+    pub fn report_answer(&self, source: &InputSource, part: u32, answer: &impl fmt::Display) {
+        match self.check_answer(source, part, answer) {
+            Ok(AnswerCheck::Unknown) => {}
+            Ok(AnswerCheck::Correct) => eprintln!("part {} matches the known answer", part),
+            Ok(AnswerCheck::Wrong { expected }) => {
+                eprintln!("part {} is wrong - the known answer is {}", part, expected)
+            }
+            Err(e) => eprintln!("part {} couldn't be checked - {}", part, e),
+        }
+    }
+
+    /// opens the input for reading, without loading it all at once
+    pub fn open(&self, source: &InputSource) -> Result<Box<dyn Read>, InputError> {
+        let io_error = |err: std::io::Error| InputError::Io {
+            source: source.clone(),
+            err,
+        };
+        match source {
+            InputSource::Stdin => Ok(Box::new(std::io::stdin())),
+            InputSource::Path(path) => Ok(Box::new(std::fs::File::open(path).map_err(io_error)?)),
+            InputSource::Named(name) => {
+                let tried: Vec<PathBuf> = self.candidate_paths(*name);
+                if let Some(path) = tried.iter().find(|path| path.is_file()) {
+                    return Ok(Box::new(std::fs::File::open(path).map_err(io_error)?));
+                }
+                match self.embedded(*name) {
+                    Some(contents) => Ok(Box::new(contents.as_bytes())),
+                    None => Err(InputError::Missing {
+                        day: self.day,
+                        name: *name,
+                        tried,
+                    }),
+                }
+            }
+        }
+    }
+
+    /// loads the whole input into a string
+    pub fn load(&self, source: &InputSource) -> Result<String, InputError> {
+        let mut input: String = String::new();
+        self.open(source)?
+            .read_to_string(&mut input)
+            .map_err(|err| InputError::Io {
+                source: source.clone(),
+                err,
+            })?;
+        Ok(input)
+    }
+
+    /// loads the input, exiting with a readable error if it can't be found
+    /// or read
+    pub fn load_or_exit(&self, source: &InputSource) -> String {
+        self.load(source).unwrap_or_else(|e| {
+            eprintln!("failed to load input - {}", e);
+            std::process::exit(1);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::tests::scratch_dir;
+    use crate::cache::Answer;
+
+    #[test]
+    fn answers_are_checked_against_the_cache() {
+        let root: PathBuf = scratch_dir("checked_answers");
+        let cache: Cache = Cache::new(&root);
+        cache
+            .record_answer(
+                YEAR,
+                2,
+                Answer {
+                    name: InputName::Real,
+                    part: 1,
+                    answer: String::from("246"),
+                },
+            )
+            .unwrap();
+        let inputs: DayInputs = DayInputs::new(2, &root).with_cache(Some(cache));
+
+        let real: InputSource = InputSource::Named(InputName::Real);
+        let check = |source: &InputSource, part: u32, answer: usize| {
+            inputs.check_answer(source, part, &answer).unwrap()
+        };
+        assert_eq!(check(&real, 1, 246), AnswerCheck::Correct);
+        assert_eq!(
+            check(&real, 1, 247),
+            AnswerCheck::Wrong {
+                expected: String::from("246")
+            }
+        );
+        assert_eq!(check(&real, 2, 318), AnswerCheck::Unknown);
+        assert_eq!(check(&InputSource::Stdin, 1, 247), AnswerCheck::Unknown);
+        assert_eq!(
+            check(&InputSource::Named(InputName::Example(1)), 1, 247),
+            AnswerCheck::Unknown
+        );
+
+        // without a cache, nothing is known
+        let uncached: DayInputs = DayInputs::new(2, &root).with_cache(None);
+        assert_eq!(
+            uncached.check_answer(&real, 1, &247).unwrap(),
+            AnswerCheck::Unknown
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn input_names_round_trip() {
+        for (name_str, name, file_name) in [
+            ("real", InputName::Real, "input.txt"),
+            ("example", InputName::Example(1), "test-input.txt"),
+            ("example2", InputName::Example(2), "test-input-2.txt"),
+        ] {
+            assert_eq!(name_str.parse::<InputName>(), Ok(name));
+            assert_eq!(name.to_string(), name_str);
+            assert_eq!(name.file_name(), file_name);
+        }
+        assert_eq!("example1".parse::<InputName>(), Ok(InputName::Example(1)));
+        for bad in ["example0", "example-1", "examples", "Real", ""] {
+            assert_eq!(
+                bad.parse::<InputName>(),
+                Err(format!(
+                    "unknown input '{}', expected real, example or example<n>",
+                    bad
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn sources_are_parsed_from_args() {
+        assert_eq!(InputSource::from_arg("-"), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg("example2"),
+            InputSource::Named(InputName::Example(2))
+        );
+        assert_eq!(
+            InputSource::from_arg("example0"),
+            InputSource::Path(PathBuf::from("example0"))
+        );
+        assert_eq!(
+            InputSource::from_arg("./input.txt"),
+            InputSource::Path(PathBuf::from("./input.txt"))
+        );
+    }
+
+    #[test]
+    fn named_inputs_prefer_the_cache_then_the_crate_then_embedded() {
+        let root: PathBuf = scratch_dir("resolution_order");
+        let cache: Cache = Cache::new(root.join("cache"));
+        let crate_dir: PathBuf = root.join("day_2");
+        std::fs::create_dir_all(&crate_dir).unwrap();
+        let inputs: DayInputs = DayInputs::new(2, &crate_dir)
+            .with_cache(Some(cache.clone()))
+            .embed(InputName::Example(1), "embedded");
+        let example: InputSource = InputSource::Named(InputName::Example(1));
+        assert_eq!(
+            inputs.candidate_paths(InputName::Example(1)),
+            [
+                cache.input_path(YEAR, 2, InputName::Example(1)),
+                crate_dir.join("test-input.txt"),
+            ]
+        );
+
+        assert_eq!(inputs.load(&example).unwrap(), "embedded");
+        std::fs::write(crate_dir.join("test-input.txt"), "crate").unwrap();
+        assert_eq!(inputs.load(&example).unwrap(), "crate");
+        let from: PathBuf = root.join("downloaded.txt");
+        std::fs::write(&from, "cached").unwrap();
+        cache.import(YEAR, 2, InputName::Example(1), &from).unwrap();
+        assert_eq!(inputs.load(&example).unwrap(), "cached");
+
+        // paths are read as-is, skipping everything else
+        assert_eq!(
+            inputs.load(&InputSource::Path(from.clone())).unwrap(),
+            "cached"
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn missing_inputs_list_where_they_were_looked_for() {
+        let root: PathBuf = scratch_dir("missing_inputs");
+        let inputs: DayInputs = DayInputs::new(2, root.join("day_2"))
+            .with_cache(Some(Cache::new(root.join("cache"))))
+            .embed(InputName::Example(1), "embedded");
+
+        let err: InputError = inputs
+            .load(&InputSource::Named(InputName::Real))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "no real input for day 2 - looked for {}, {} (import one with aoc_cache)",
+                root.join("cache/2024/day_02/input.txt").display(),
+                root.join("day_2/input.txt").display()
+            )
+        );
+
+        let missing_path: PathBuf = root.join("nowhere.txt");
+        let err: InputError = inputs
+            .load(&InputSource::Path(missing_path.clone()))
+            .unwrap_err();
+        assert!(matches!(err, InputError::Io { .. }));
+        assert!(
+            err.to_string()
+                .starts_with(&format!("couldn't read {} - ", missing_path.display())),
+            "{}",
+            err
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    // builds an Args from a space-separated command line
+    fn args(line: &str) -> Args {
+        Args::new(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn input_is_picked_out_of_the_args() {
+        let mut parsed: Args = args("--render --input example2 0..=3 --input -");
+        assert_eq!(parsed.try_next(), Ok(Some(String::from("--render"))));
+        assert_eq!(parsed.try_next(), Ok(Some(String::from("0..=3"))));
+        assert_eq!(parsed.source(), &InputSource::Named(InputName::Example(2)));
+        assert_eq!(parsed.try_next(), Ok(None));
+        assert_eq!(parsed.source(), &InputSource::Stdin);
+
+        assert_eq!(args("").try_finish(), Ok(InputSource::default()));
+        assert_eq!(
+            args("--input ./in.txt").try_finish(),
+            Ok(InputSource::Path(PathBuf::from("./in.txt")))
+        );
+        assert_eq!(
+            args("--input example -").try_finish(),
+            Err(ArgError::Unknown(String::from("-")))
+        );
+        for line in ["--input", "--input --general"] {
+            assert_eq!(
+                args(line).try_finish(),
+                Err(ArgError::MissingValue(String::from("--input")))
+            );
+        }
+    }
+
+    #[test]
+    fn flag_values_are_parsed_or_reported() {
+        let parse_usize = |value: &str| value.parse::<usize>();
+        let mut parsed: Args = args("--bench --metric l2 --bench 10 --digits abc --removals");
+        assert_eq!(parsed.try_next(), Ok(Some(String::from("--bench"))));
+        assert_eq!(
+            parsed.try_optional_value_with("--bench", parse_usize),
+            Ok(None)
+        );
+        assert_eq!(parsed.try_next(), Ok(Some(String::from("--metric"))));
+        assert_eq!(parsed.value::<String>("--metric"), "l2");
+        assert_eq!(parsed.try_next(), Ok(Some(String::from("--bench"))));
+        assert_eq!(parsed.optional_value::<usize>("--bench"), Some(10));
+
+        assert_eq!(parsed.try_next(), Ok(Some(String::from("--digits"))));
+        let err: ArgError = parsed.try_value_with("--digits", parse_usize).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid value 'abc' for --digits - invalid digit found in string"
+        );
+
+        assert_eq!(parsed.try_next(), Ok(Some(String::from("--removals"))));
+        assert_eq!(
+            parsed.try_value_with("--removals", parse_usize),
+            Err(ArgError::MissingValue(String::from("--removals")))
+        );
+        assert_eq!(
+            ArgError::MissingValue(String::from("--removals")).to_string(),
+            "--removals needs a value"
+        );
+    }
+}
@@ -0,0 +1,11 @@
+/* file:    lib.rs
+ * author:  garnt
+ * date:    01/04/2025
+ * desc:    Helpers shared between the Advent of Code 2024 days.
+ */
+
+pub mod cache;
+pub mod input;
+pub mod rng;
+pub mod spatial_index;
+pub mod table;
@@ -0,0 +1,34 @@
+/* file:    rng.rs
+ * author:  garnt
+ * date:    01/04/2025
+ * desc:    A small seeded pseudo-random generator, for synthetic inputs that
+ *          have to come out the same on every run.
+ */
+
+/// a xorshift64 generator. it's fast and reproducible from its seed, but
+/// nowhere near good enough for anything that needs real randomness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    /// constructs a new XorShift from a seed. a seed of 0 would only ever
+    /// produce 0, so it's treated as 1.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    /// returns the next value in the sequence
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// returns the next value in the range [0, bound). bound must not be 0.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
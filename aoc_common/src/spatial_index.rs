@@ -0,0 +1,285 @@
+/* file:    spatial_index.rs
+ * author:  garnt
+ * date:    12/27/2024
+ * desc:    An index of labelled points on a 2d grid, bucketed by position so
+ *          region and nearest-point queries don't have to scan every point.
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// a point on the grid, as (x, y)
+pub type Coord = (isize, isize);
+
+/// the width and height of the square each bucket covers, unless told otherwise
+const DEFAULT_BUCKET_SIZE: isize = 8;
+
+/// a multi-map from labels to every grid position holding that label, which
+/// also keeps every point in a bucket_size x bucket_size bucket so queries
+/// only look at the buckets they overlap
+#[derive(Clone, Debug)]
+pub struct SpatialIndex<L> {
+    by_label: HashMap<L, Vec<Coord>>,
+    buckets: HashMap<Coord, Vec<(L, Coord)>>,
+    bucket_size: isize,
+}
+
+impl SpatialIndex<char> {
+    /// builds an index of every char in a grid of lines, skipping any char for
+    /// which is_empty returns true
+    pub fn from_grid<F>(input: &str, is_empty: F) -> Self
+    where
+        F: Fn(char) -> bool,
+    {
+        let mut index: Self = Self::new();
+        for (y, line) in input.lines().enumerate() {
+            for (x, cur_char) in line.chars().enumerate() {
+                if !is_empty(cur_char) {
+                    index.insert(cur_char, (x as isize, y as isize));
+                }
+            }
+        }
+
+        index
+    }
+}
+
+// returns every bucket exactly ring buckets away from centre, i.e. the edge of
+// the (2 * ring + 1)-wide square around it
+fn ring_edge(centre: Coord, ring: isize) -> Vec<Coord> {
+    if ring == 0 {
+        return vec![centre];
+    }
+    let (x, y) = centre;
+    let rows = (-ring..=ring).flat_map(|dx| [(x + dx, y - ring), (x + dx, y + ring)]);
+    let cols = (1 - ring..ring).flat_map(|dy| [(x - ring, y + dy), (x + ring, y + dy)]);
+    rows.chain(cols).collect()
+}
+
+impl<L> Default for SpatialIndex<L> {
+    fn default() -> Self {
+        Self {
+            by_label: HashMap::new(),
+            buckets: HashMap::new(),
+            bucket_size: DEFAULT_BUCKET_SIZE,
+        }
+    }
+}
+
+impl<L> SpatialIndex<L>
+where
+    L: Copy + Eq + Hash,
+{
+    /// constructs a new, empty SpatialIndex
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// constructs a new, empty SpatialIndex whose buckets cover
+    /// bucket_size x bucket_size squares. panics if bucket_size isn't positive.
+    pub fn with_bucket_size(bucket_size: isize) -> Self {
+        assert!(bucket_size > 0, "bucket size must be positive");
+        Self {
+            bucket_size,
+            ..Self::default()
+        }
+    }
+
+    // returns the bucket a point falls in
+    fn bucket_of(&self, coord: Coord) -> Coord {
+        (
+            coord.0.div_euclid(self.bucket_size),
+            coord.1.div_euclid(self.bucket_size),
+        )
+    }
+
+    /// adds a point under the provided label
+    pub fn insert(&mut self, label: L, coord: Coord) {
+        self.by_label.entry(label).or_default().push(coord);
+        self.buckets
+            .entry(self.bucket_of(coord))
+            .or_default()
+            .push((label, coord));
+    }
+
+    /// returns every point with the provided label, in insertion order
+    pub fn get(&self, label: L) -> &[Coord] {
+        self.by_label
+            .get(&label)
+            .map_or(&[], |coords| coords.as_slice())
+    }
+
+    /// returns an iterator over every (label, point) pair in the index
+    pub fn iter(&self) -> impl Iterator<Item = (L, Coord)> + '_ {
+        self.by_label
+            .iter()
+            .flat_map(|(label, coords)| coords.iter().map(move |coord| (*label, *coord)))
+    }
+
+    /// returns every (label, point) pair within the rectangle [min, max),
+    /// looking only at the buckets the rectangle overlaps
+    pub fn in_region(&self, min: Coord, max: Coord) -> Vec<(L, Coord)> {
+        if min.0 >= max.0 || min.1 >= max.1 {
+            return Vec::new();
+        }
+        let in_rect = |coord: &Coord| {
+            coord.0 >= min.0 && coord.0 < max.0 && coord.1 >= min.1 && coord.1 < max.1
+        };
+
+        // a region much bigger than the points in it would mean walking lots of
+        // empty buckets, so just check the occupied ones instead
+        let (min_bucket, max_bucket) =
+            (self.bucket_of(min), self.bucket_of((max.0 - 1, max.1 - 1)));
+        let n_overlapped: usize = ((max_bucket.0 - min_bucket.0 + 1) as usize)
+            .saturating_mul((max_bucket.1 - min_bucket.1 + 1) as usize);
+        let bucket_points: Vec<&Vec<(L, Coord)>> = if n_overlapped > self.buckets.len() {
+            self.buckets.values().collect()
+        } else {
+            (min_bucket.1..=max_bucket.1)
+                .flat_map(|y| (min_bucket.0..=max_bucket.0).map(move |x| (x, y)))
+                .filter_map(|bucket| self.buckets.get(&bucket))
+                .collect()
+        };
+
+        bucket_points
+            .into_iter()
+            .flatten()
+            .filter(|(_, coord)| in_rect(coord))
+            .copied()
+            .collect()
+    }
+
+    /// returns the point closest to the provided one by manhattan distance,
+    /// optionally restricted to a single label. ties are broken by the lowest
+    /// (y, x). searches outwards one ring of buckets at a time, stopping once
+    /// no unsearched bucket could hold anything closer.
+    pub fn nearest(&self, point: Coord, label: Option<L>) -> Option<(L, Coord)> {
+        let centre: Coord = self.bucket_of(point);
+        // the furthest ring of buckets that holds any points at all
+        let max_ring: isize = self
+            .buckets
+            .keys()
+            .map(|bucket| (bucket.0 - centre.0).abs().max((bucket.1 - centre.1).abs()))
+            .max()?;
+
+        // keeps the closer of best and each of the provided points
+        let consider = |best: &mut Option<(usize, (L, Coord))>, points: &[(L, Coord)]| {
+            for (cur_label, coord) in points {
+                if label.is_some_and(|wanted| wanted != *cur_label) {
+                    continue;
+                }
+                let dist: usize = point.0.abs_diff(coord.0) + point.1.abs_diff(coord.1);
+                let is_better = best.is_none_or(|(best_dist, (_, best_coord))| {
+                    (dist, coord.1, coord.0) < (best_dist, best_coord.1, best_coord.0)
+                });
+                if is_better {
+                    *best = Some((dist, (*cur_label, *coord)));
+                }
+            }
+        };
+
+        let mut best: Option<(usize, (L, Coord))> = None;
+        for ring in 0..=max_ring {
+            // every point in this ring is at least (ring - 1) * bucket_size + 1
+            // away, so a close enough best can't be beaten from here on
+            if let Some((best_dist, _)) = best {
+                if ring > 0 && best_dist <= ((ring - 1) * self.bucket_size) as usize {
+                    break;
+                }
+            }
+
+            // a point far from everything would mean walking lots of empty
+            // rings, so once the square searched so far covers more buckets
+            // than are occupied, just check the occupied ones instead
+            let side: usize = (2 * ring + 1) as usize;
+            if side.saturating_mul(side) > self.buckets.len() {
+                for points in self.buckets.values() {
+                    consider(&mut best, points);
+                }
+                break;
+            }
+
+            for bucket in ring_edge(centre, ring) {
+                if let Some(points) = self.buckets.get(&bucket) {
+                    consider(&mut best, points);
+                }
+            }
+        }
+
+        best.map(|(_, found)| found)
+    }
+
+    /// returns an iterator over every unordered pair of points sharing a label
+    pub fn pairs(&self) -> impl Iterator<Item = (L, [Coord; 2])> + '_ {
+        self.by_label.iter().flat_map(|(label, coords)| {
+            coords.iter().enumerate().flat_map(move |(idx, a)| {
+                coords[(idx + 1)..].iter().map(move |b| (*label, [*a, *b]))
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a scattering of labelled points, including some negative ones
+    fn scattered_points() -> Vec<(char, Coord)> {
+        (0..300)
+            .map(|idx: isize| {
+                let label: char = ['a', 'b', 'c', 'd'][(idx % 4) as usize];
+                (label, ((idx * 37) % 61 - 20, (idx * 53) % 47 - 10))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn queries_match_a_linear_scan() {
+        let points: Vec<(char, Coord)> = scattered_points();
+        for bucket_size in [1, 3, 8, 100] {
+            let mut index: SpatialIndex<char> = SpatialIndex::with_bucket_size(bucket_size);
+            for (label, coord) in &points {
+                index.insert(*label, *coord);
+            }
+
+            for (min, max) in [
+                ((-5, -5), (6, 7)),
+                ((-100, -100), (100, 100)),
+                ((3, 3), (3, 9)),
+            ] {
+                let mut expected: Vec<(char, Coord)> = points
+                    .iter()
+                    .filter(|(_, c)| c.0 >= min.0 && c.0 < max.0 && c.1 >= min.1 && c.1 < max.1)
+                    .copied()
+                    .collect();
+                let mut found: Vec<(char, Coord)> = index.in_region(min, max);
+                expected.sort();
+                found.sort();
+                assert_eq!(found, expected, "region [{:?}, {:?})", min, max);
+            }
+
+            // including some far enough away to need the occupied-bucket scan
+            let queries: [Coord; 6] = [
+                (0, 0),
+                (-40, 3),
+                (90, -60),
+                (17, 22),
+                (2_000_000, -3_000_000),
+                (-9_000_000_000, 9_000_000_000),
+            ];
+            for point in queries {
+                for label in [None, Some('a'), Some('d'), Some('z')] {
+                    let expected: Option<usize> = points
+                        .iter()
+                        .filter(|(cur, _)| label.is_none_or(|wanted| wanted == *cur))
+                        .map(|(_, c)| point.0.abs_diff(c.0) + point.1.abs_diff(c.1))
+                        .min();
+                    let found: Option<usize> = index
+                        .nearest(point, label)
+                        .map(|(_, c)| point.0.abs_diff(c.0) + point.1.abs_diff(c.1));
+                    assert_eq!(found, expected, "nearest to {:?} with {:?}", point, label);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+/* file:    table.rs
+ * author:  garnt
+ * date:    01/04/2025
+ * desc:    Parser for inputs made up of columns of numbers, like day 1's.
+ */
+
+use std::fmt;
+use std::str::FromStr;
+
+/// how the values on each line are separated
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Delimiter {
+    /// any run of whitespace
+    Whitespace,
+    /// a single character, with any whitespace around values ignored
+    Char(char),
+}
+
+/// an error encountered while parsing a table
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableError {
+    /// the 1-based line number the error was found on
+    pub line_no: usize,
+    /// the 1-based column the error was found in, if it's about one value
+    pub column: Option<usize>,
+    pub msg: String,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}: {}", self.line_no, column, self.msg),
+            None => write!(f, "line {}: {}", self.line_no, self.msg),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// parses every non-blank line of the input into columns of values, returned
+/// column-major, so that columns[i] holds every value in the i-th column. if
+/// n_columns isn't provided, every line must have as many columns as the
+/// first. lines may end in either "\n" or "\r\n".
+pub fn parse_columns<T>(
+    input: &str,
+    delimiter: Delimiter,
+    n_columns: Option<usize>,
+) -> Result<Vec<Vec<T>>, TableError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let mut columns: Vec<Vec<T>> = Vec::new();
+    let lines = input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim_end_matches('\r')))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    for (line_no, line) in lines {
+        let fields: Vec<&str> = match delimiter {
+            Delimiter::Whitespace => line.split_whitespace().collect(),
+            Delimiter::Char(c) => line.split(c).map(str::trim).collect(),
+        };
+
+        // the first line decides how many columns there are, unless we were
+        // told up front
+        let expected: usize = match (n_columns, columns.is_empty()) {
+            (Some(n_columns), _) => n_columns,
+            (None, true) => fields.len(),
+            (None, false) => columns.len(),
+        };
+        if fields.len() != expected {
+            return Err(TableError {
+                line_no,
+                column: None,
+                msg: format!("expected {} columns, found {}", expected, fields.len()),
+            });
+        }
+        if columns.is_empty() {
+            columns.resize_with(expected, Vec::new);
+        }
+
+        for (col_idx, field) in fields.iter().enumerate() {
+            let val: T = field.parse::<T>().map_err(|e| TableError {
+                line_no,
+                column: Some(col_idx + 1),
+                msg: format!("invalid value '{}': {}", field, e),
+            })?;
+            columns[col_idx].push(val);
+        }
+    }
+
+    // an empty input still has the columns we were asked for
+    if columns.is_empty() {
+        columns.resize_with(n_columns.unwrap_or(0), Vec::new);
+    }
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delimiters_and_line_endings() {
+        let input: &str = "3   4\r\n\r\n4 3\r\n  \n2\t5\r\n\r\n";
+        assert_eq!(
+            parse_columns::<i64>(input, Delimiter::Whitespace, Some(2)),
+            Ok(vec![vec![3, 4, 2], vec![4, 3, 5]])
+        );
+
+        let input: &str = " 1, -2 ,3\r\n\r\n4 ,5,  6 \n";
+        assert_eq!(
+            parse_columns::<i64>(input, Delimiter::Char(','), None),
+            Ok(vec![vec![1, 4], vec![-2, 5], vec![3, 6]])
+        );
+    }
+
+    #[test]
+    fn empty_input_has_the_requested_columns() {
+        let empty: Vec<Vec<u8>> = vec![Vec::new(); 3];
+        assert_eq!(
+            parse_columns::<u8>("", Delimiter::Whitespace, Some(3)),
+            Ok(empty.clone())
+        );
+        assert_eq!(
+            parse_columns::<u8>("\r\n \n", Delimiter::Char(','), Some(3)),
+            Ok(empty)
+        );
+        assert_eq!(
+            parse_columns::<u8>("", Delimiter::Whitespace, None),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn errors_name_the_line_and_column() {
+        let error = |input: &str, delimiter: Delimiter, n_columns: Option<usize>| {
+            parse_columns::<i64>(input, delimiter, n_columns)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("1 2\n\n3 x\n", Delimiter::Whitespace, Some(2)),
+            "line 3, column 2: invalid value 'x': invalid digit found in string"
+        );
+        assert_eq!(
+            error("1,2\n3,\n", Delimiter::Char(','), None),
+            "line 2, column 2: invalid value '': cannot parse integer from empty string"
+        );
+
+        // without a column count, the first line sets it
+        assert_eq!(
+            error("1 2 3\n4 5 6\n7 8\n", Delimiter::Whitespace, None),
+            "line 3: expected 3 columns, found 2"
+        );
+        assert_eq!(
+            error("\r\n1 2 3\r\n", Delimiter::Whitespace, Some(2)),
+            "line 2: expected 2 columns, found 3"
+        );
+    }
+}
@@ -0,0 +1,82 @@
+/* file:    bench.rs
+ * author:  garnt
+ * date:    12/20/2024
+ * desc:    Rough timings of day 1's similarity scores on synthetic input.
+ */
+
+use crate::similarity;
+use aoc_common::rng::XorShift;
+use std::time::{Duration, Instant};
+
+/// the largest input the O(n^2) approach is run on by default before it gets
+/// too slow to wait for. past this, its time is extrapolated from the last run.
+pub const MAX_NAIVE_LINES: usize = 100_000;
+
+/// generates n_lines of synthetic 5-digit left and right columns, like the
+/// real input, using a fixed seed so runs are comparable
+pub fn synthetic_columns(n_lines: usize, seed: u64) -> (Vec<i64>, Vec<i64>) {
+    let mut rng = XorShift::new(seed);
+    let mut next = move || 10_000 + rng.below(90_000) as i64;
+
+    (0..n_lines).map(|_| (next(), next())).unzip()
+}
+
+// runs f once, returning its result and how long it took
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start: Instant = Instant::now();
+    let result: T = f();
+    (result, start.elapsed())
+}
+
+/// times each similarity score on synthetic inputs of increasing size, up to
+/// max_lines, and prints a table of the results. the O(n^2) approach is only
+/// run on inputs of up to max_naive_lines, and its time on bigger ones is
+/// estimated by scaling its last real run by the square of the size.
+pub fn run(max_lines: usize, max_naive_lines: usize) {
+    println!(
+        "{:>9} | {:>12} | {:>12} | {:>12}",
+        "lines", "iter_cmp", "histogram", "sorted merge"
+    );
+
+    let mut last_naive: Option<(usize, Duration)> = None;
+    let mut n_lines: usize = 1_000;
+    while n_lines <= max_lines {
+        let (mut lefts, mut rights) = synthetic_columns(n_lines, 0x2024_1201);
+        let (histogram, histogram_time) = time(|| similarity::histogram_cmp(&lefts, &rights));
+        let naive: Option<(Option<i64>, Duration)> =
+            (n_lines <= max_naive_lines).then(|| time(|| similarity::iter_cmp(&lefts, &rights)));
+
+        // the merge needs sorted lists, so count the sort against it
+        let (merge, merge_time) = time(|| {
+            lefts.sort();
+            rights.sort();
+            similarity::merge_cmp(&lefts, &rights)
+        });
+
+        // every approach has to agree, or the timings don't mean anything
+        assert_eq!(histogram, merge);
+        if let Some((naive, _)) = naive {
+            assert_eq!(histogram, naive);
+        }
+
+        let naive_str: String = match (naive, last_naive) {
+            (Some((_, naive_time)), _) => {
+                last_naive = Some((n_lines, naive_time));
+                format!("{:.2?}", naive_time)
+            }
+            (None, Some((last_lines, last_time))) => {
+                let scale: f64 = (n_lines as f64 / last_lines as f64).powi(2);
+                format!("~{:.0?} est.", last_time.mul_f64(scale))
+            }
+            (None, None) => String::from("skipped"),
+        };
+        println!(
+            "{:>9} | {:>12} | {:>12} | {:>12}",
+            n_lines,
+            naive_str,
+            format!("{:.2?}", histogram_time),
+            format!("{:.2?}", merge_time)
+        );
+        n_lines *= 10;
+    }
+}
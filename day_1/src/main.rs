@@ -4,39 +4,60 @@
  * desc:    Advent of Code 2024 day 1.
  */
 
-fn iter_cmp(lefts: &Vec<i32>, rights: &Vec<i32>) -> i32 {
-    let mut similar: i32 = 0;
-    for l_val in lefts {
-        similar += l_val * rights.iter().filter(|r_val| *l_val == **r_val).count() as i32;
-    }
-    return similar;
-}
+mod bench;
+mod metrics;
+mod similarity;
+
+use aoc_common::input::{ArgError, Args, DayInputs, InputSource};
+use aoc_common::table::{self, Delimiter};
+use metrics::Metric;
 
 fn main() {
-    let mut lefts: Vec<i32> = Vec::new();
-    let mut rights: Vec<i32> = Vec::new();
-    for line in std::fs::read_to_string("input.txt").unwrap().lines() {
-        let mut halves_iter = line.split_whitespace().take(2);
-        lefts.push(halves_iter.next().unwrap().parse::<i32>().unwrap());
-        rights.push(halves_iter.next().unwrap().parse::<i32>().unwrap());
+    // parse the optional arguments, e.g. `day_1 --bench 1000000 --naive-up-to
+    // 1000000` or
+    // `day_1 --metric kendall-tau --metric l2` or `day_1 --metric all --input -`
+    let mut bench_lines: Option<usize> = None;
+    let mut max_naive_lines: usize = bench::MAX_NAIVE_LINES;
+    let mut metrics: Vec<Metric> = Vec::new();
+    let mut args = Args::from_env();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bench" => bench_lines = Some(args.optional_value(&arg).unwrap_or(1_000_000)),
+            "--naive-up-to" => max_naive_lines = args.value(&arg),
+            "--metric" => metrics.extend(args.value_with(&arg, |name| match name {
+                "all" => Ok(Metric::ALL.to_vec()),
+                _ => name.parse().map(|metric| vec![metric]),
+            })),
+            _ => ArgError::Unknown(arg).exit(),
+        }
+    }
+    let source: InputSource = args.source().clone();
+    if let Some(max_lines) = bench_lines {
+        bench::run(max_lines, max_naive_lines);
+        return;
     }
-    lefts.sort();
-    rights.sort();
 
-    let mut pairs: Vec<(i32, i32)> = Vec::new();
-    pairs = lefts
-        .iter()
-        .zip(rights.iter())
-        .map(|refs| (refs.0.to_owned(), refs.1.to_owned()))
-        .collect();
+    // read in the left and right columns
+    let inputs = DayInputs::new(1, env!("CARGO_MANIFEST_DIR"));
+    let input: String = inputs.load_or_exit(&source);
+    let [lefts, rights]: [Vec<i64>; 2] =
+        match table::parse_columns::<i64>(&input, Delimiter::Whitespace, Some(2)) {
+            Ok(columns) => columns.try_into().unwrap(),
+            Err(e) => {
+                eprintln!("failed to parse {} - {}", source, e);
+                std::process::exit(1);
+            }
+        };
 
-    println!(
-        "pt 1: {:?}",
-        pairs
-            .iter()
-            .map(|tuple| (tuple.0 - tuple.1).abs())
-            .sum::<i32>()
-    );
+    // check each part against its known answer, if there is one
+    for (part, metric) in [(1, Metric::L1), (2, Metric::Similarity)] {
+        let answer = metric.compute(&lefts, &rights);
+        println!("pt {}: {}", part, answer);
+        inputs.report_answer(&source, part, &answer);
+    }
 
-    println!("pt 2: {}", iter_cmp(&lefts, &rights));
+    // optionally compare the columns in other ways
+    for metric in metrics {
+        println!("{}: {}", metric.name(), metric.compute(&lefts, &rights));
+    }
 }
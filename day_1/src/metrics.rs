@@ -0,0 +1,226 @@
+/* file:    metrics.rs
+ * author:  garnt
+ * date:    12/20/2024
+ * desc:    Ways of comparing two lists of numbers, selectable by name.
+ */
+
+use crate::similarity;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// a way of comparing the left and right columns
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Metric {
+    /// the sum of the distances between the sorted columns' pairs (part 1)
+    L1,
+    /// the euclidean distance between the sorted columns
+    L2,
+    /// each left value times how often it appears on the right (part 2)
+    Similarity,
+    /// the kendall tau-b rank correlation between the columns, pairing values
+    /// on the same line
+    KendallTau,
+    /// how many values the columns have in common, counting repeats
+    Intersection,
+}
+
+impl Metric {
+    /// every metric, in the order they're listed
+    pub const ALL: [Metric; 5] = [
+        Metric::L1,
+        Metric::L2,
+        Metric::Similarity,
+        Metric::KendallTau,
+        Metric::Intersection,
+    ];
+
+    /// the name the metric is selected by
+    pub fn name(&self) -> &'static str {
+        match self {
+            Metric::L1 => "l1",
+            Metric::L2 => "l2",
+            Metric::Similarity => "similarity",
+            Metric::KendallTau => "kendall-tau",
+            Metric::Intersection => "intersection",
+        }
+    }
+
+    /// compares the two columns, which should be in their original line order.
+    /// metrics that pair values up ignore any extra values in the longer
+    /// column.
+    pub fn compute(&self, lefts: &[i64], rights: &[i64]) -> MetricValue {
+        match self {
+            Metric::L1 => MetricValue::Int(
+                sorted_distances(lefts, rights)
+                    .into_iter()
+                    .map(i128::from)
+                    .sum(),
+            ),
+            Metric::L2 => MetricValue::Float(
+                sorted_distances(lefts, rights)
+                    .into_iter()
+                    .map(|dist| (dist as f64).powi(2))
+                    .sum::<f64>()
+                    .sqrt(),
+            ),
+            Metric::Similarity => {
+                let lefts: Vec<i128> = lefts.iter().map(|val| i128::from(*val)).collect();
+                let rights: Vec<i128> = rights.iter().map(|val| i128::from(*val)).collect();
+                similarity::histogram_cmp(&lefts, &rights)
+                    .map_or(MetricValue::Overflow, MetricValue::Int)
+            }
+            Metric::KendallTau => MetricValue::Float(kendall_tau(lefts, rights)),
+            Metric::Intersection => {
+                let mut counts: HashMap<i64, usize> = HashMap::new();
+                for r_val in rights {
+                    *counts.entry(*r_val).or_insert(0) += 1;
+                }
+                let mut shared: usize = 0;
+                for l_val in lefts {
+                    if let Some(count) = counts.get_mut(l_val).filter(|count| **count > 0) {
+                        *count -= 1;
+                        shared += 1;
+                    }
+                }
+                MetricValue::Int(shared as i128)
+            }
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Metric::ALL
+            .into_iter()
+            .find(|metric| metric.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Metric::ALL.iter().map(Metric::name).collect();
+                format!(
+                    "unknown metric '{}', expected one of {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// the result of comparing two columns
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MetricValue {
+    Int(i128),
+    Float(f64),
+    /// the value was too big to fit in an i128
+    Overflow,
+}
+
+impl fmt::Display for MetricValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetricValue::Int(val) => write!(f, "{}", val),
+            MetricValue::Float(val) => write!(f, "{:.6}", val),
+            MetricValue::Overflow => write!(f, "too big to fit in an i128"),
+        }
+    }
+}
+
+/// sorts both columns, and returns the distance between each pair of values
+/// in the same position
+fn sorted_distances(lefts: &[i64], rights: &[i64]) -> Vec<u64> {
+    let mut sorted_lefts: Vec<i64> = lefts.to_vec();
+    let mut sorted_rights: Vec<i64> = rights.to_vec();
+    sorted_lefts.sort();
+    sorted_rights.sort();
+    sorted_lefts
+        .iter()
+        .zip(sorted_rights.iter())
+        .map(|(l_val, r_val)| l_val.abs_diff(*r_val))
+        .collect()
+}
+
+/// the kendall tau-b rank correlation between the pairs (lefts[i], rights[i]),
+/// which accounts for ties. compares every pair of lines, so it's O(n^2).
+/// returns NaN if either column is constant.
+fn kendall_tau(lefts: &[i64], rights: &[i64]) -> f64 {
+    let n: usize = lefts.len().min(rights.len());
+    let (mut concordant, mut discordant) = (0i64, 0i64);
+    let (mut left_ties, mut right_ties) = (0i64, 0i64);
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let l_cmp = lefts[i].cmp(&lefts[j]);
+            let r_cmp = rights[i].cmp(&rights[j]);
+            if l_cmp.is_eq() {
+                left_ties += 1;
+            }
+            if r_cmp.is_eq() {
+                right_ties += 1;
+            }
+            if l_cmp.is_ne() && r_cmp.is_ne() {
+                if l_cmp == r_cmp {
+                    concordant += 1;
+                } else {
+                    discordant += 1;
+                }
+            }
+        }
+    }
+
+    let n_pairs: i64 = (n * n.saturating_sub(1) / 2) as i64;
+    let denominator: f64 =
+        (((n_pairs - left_ties) as f64) * ((n_pairs - right_ties) as f64)).sqrt();
+    (concordant - discordant) as f64 / denominator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the puzzle's example, in line order
+    const LEFTS: [i64; 6] = [3, 4, 2, 1, 3, 3];
+    const RIGHTS: [i64; 6] = [4, 3, 5, 3, 9, 3];
+
+    #[test]
+    fn example_metrics() {
+        let compute = |metric: Metric| metric.compute(&LEFTS, &RIGHTS);
+        assert_eq!(compute(Metric::L1), MetricValue::Int(11));
+        assert_eq!(compute(Metric::Similarity), MetricValue::Int(31));
+
+        // the sorted pairs are 2, 1, 0, 1, 2 and 5 apart
+        assert_eq!(compute(Metric::L2), MetricValue::Float(35f64.sqrt()));
+
+        // the 1 and 2 on the left have no match, and each 3 on the left uses up
+        // one of the 3s on the right
+        assert_eq!(compute(Metric::Intersection), MetricValue::Int(4));
+
+        // of the 15 pairs of lines, 4 are concordant and 5 discordant, and each
+        // column has 3 ties, so tau-b is -1 / sqrt(12 * 12) rather than tau-a's
+        // -1 / 15
+        assert_eq!(compute(Metric::KendallTau), MetricValue::Float(-1.0 / 12.0));
+    }
+
+    #[test]
+    fn kendall_tau_ties() {
+        // 2 concordant pairs, and 1 tie on the right only
+        let tau: f64 = kendall_tau(&[1, 2, 3], &[1, 1, 2]);
+        assert!((tau - 2.0 / 6f64.sqrt()).abs() < 1e-12, "{}", tau);
+
+        // a constant column has no ranking to correlate with
+        assert!(kendall_tau(&[5, 5, 5], &[1, 2, 3]).is_nan());
+        assert!(kendall_tau(&[1, 2, 3], &[7, 7, 7]).is_nan());
+    }
+
+    #[test]
+    fn big_values() {
+        // widening to i128 leaves plenty of room for the products
+        let value: MetricValue = Metric::Similarity.compute(&[i64::MAX; 3], &[i64::MAX; 3]);
+        assert_eq!(value, MetricValue::Int(9 * i128::from(i64::MAX)));
+        let value: MetricValue = Metric::L1.compute(&[i64::MIN; 2], &[i64::MAX; 2]);
+        assert_eq!(value, MetricValue::Int(2 * i128::from(u64::MAX)));
+        assert_eq!(
+            MetricValue::Overflow.to_string(),
+            "too big to fit in an i128"
+        );
+    }
+}
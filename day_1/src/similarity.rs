@@ -0,0 +1,145 @@
+/* file:    similarity.rs
+ * author:  garnt
+ * date:    12/20/2024
+ * desc:    Ways of calculating day 1's similarity score.
+ */
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// multiplication that returns None rather than overflowing
+pub trait CheckedMul: Sized {
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+/// addition that returns None rather than overflowing
+pub trait CheckedAdd: Sized {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+// forwards both traits to the integer types' own checked methods
+macro_rules! impl_checked {
+    ($($int:ty),*) => {
+        $(
+            impl CheckedMul for $int {
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$int>::checked_mul(self, rhs)
+                }
+            }
+
+            impl CheckedAdd for $int {
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$int>::checked_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// the integer types a similarity score can be calculated over
+pub trait Score: Copy + Ord + Hash + CheckedMul + CheckedAdd + TryFrom<usize> {}
+
+impl<T> Score for T where T: Copy + Ord + Hash + CheckedMul + CheckedAdd + TryFrom<usize> {}
+
+// returns val * count, or None if count or the product doesn't fit in T
+fn weighted<T: Score>(val: T, count: usize) -> Option<T> {
+    val.checked_mul(T::try_from(count).ok()?)
+}
+
+// adds up the scores, or returns None if any of them or the total is None
+fn checked_sum<T: Score>(scores: impl IntoIterator<Item = Option<T>>) -> Option<T> {
+    scores
+        .into_iter()
+        .try_fold(T::try_from(0).ok()?, |total, score| {
+            total.checked_add(score?)
+        })
+}
+
+/// the original approach, which rescans rights for every left value, so it's
+/// O(n^2). returns None if a count, a product or the total doesn't fit in T.
+pub fn iter_cmp<T: Score>(lefts: &[T], rights: &[T]) -> Option<T> {
+    checked_sum(lefts.iter().map(|l_val| {
+        let count: usize = rights.iter().filter(|r_val| l_val == *r_val).count();
+        weighted(*l_val, count)
+    }))
+}
+
+/// counts every right value up front, so it's O(n) expected time. returns
+/// None if a count, a product or the total doesn't fit in T.
+pub fn histogram_cmp<T: Score>(lefts: &[T], rights: &[T]) -> Option<T> {
+    let mut counts: HashMap<T, usize> = HashMap::with_capacity(rights.len());
+    for r_val in rights {
+        *counts.entry(*r_val).or_insert(0) += 1;
+    }
+
+    checked_sum(
+        lefts
+            .iter()
+            .map(|l_val| weighted(*l_val, counts.get(l_val).copied().unwrap_or(0))),
+    )
+}
+
+/// walks both lists side by side, so it's O(n) as long as both are already
+/// sorted, and gives nonsense otherwise. returns None if a count, a product or
+/// the total doesn't fit in T.
+pub fn merge_cmp<T: Score>(sorted_lefts: &[T], sorted_rights: &[T]) -> Option<T> {
+    let mut scores: Vec<Option<T>> = Vec::new();
+    let (mut l_idx, mut r_idx) = (0, 0);
+    while l_idx < sorted_lefts.len() && r_idx < sorted_rights.len() {
+        let (l_val, r_val) = (sorted_lefts[l_idx], sorted_rights[r_idx]);
+        if l_val < r_val {
+            l_idx += 1;
+        } else if l_val > r_val {
+            r_idx += 1;
+        } else {
+            // count the run of this value on both sides, then skip past them
+            let l_run: usize = sorted_lefts[l_idx..]
+                .iter()
+                .take_while(|val| **val == l_val)
+                .count();
+            let r_run: usize = sorted_rights[r_idx..]
+                .iter()
+                .take_while(|val| **val == r_val)
+                .count();
+            scores.push(weighted(l_val, l_run.checked_mul(r_run)?));
+            l_idx += l_run;
+            r_idx += r_run;
+        }
+    }
+
+    checked_sum(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_approach_agrees_on_the_example() {
+        let lefts: [i64; 6] = [3, 4, 2, 1, 3, 3];
+        let rights: [i64; 6] = [4, 3, 5, 3, 9, 3];
+        let (mut sorted_lefts, mut sorted_rights) = (lefts, rights);
+        sorted_lefts.sort();
+        sorted_rights.sort();
+        assert_eq!(iter_cmp(&lefts, &rights), Some(31));
+        assert_eq!(histogram_cmp(&lefts, &rights), Some(31));
+        assert_eq!(merge_cmp(&sorted_lefts, &sorted_rights), Some(31));
+    }
+
+    #[test]
+    fn overflow_is_none_rather_than_a_wrapped_score() {
+        // each product fits in an i8, but their total doesn't
+        let (lefts, rights): ([i8; 2], [i8; 1]) = ([100, 100], [100]);
+        assert_eq!(iter_cmp(&lefts, &rights), None);
+        assert_eq!(histogram_cmp(&lefts, &rights), None);
+        assert_eq!(merge_cmp(&lefts, &rights), None);
+
+        // and here a single product doesn't
+        let (lefts, rights): ([i8; 1], [i8; 2]) = ([100], [100, 100]);
+        assert_eq!(iter_cmp(&lefts, &rights), None);
+        assert_eq!(histogram_cmp(&lefts, &rights), None);
+        assert_eq!(merge_cmp(&lefts, &rights), None);
+    }
+}
@@ -4,6 +4,9 @@
  * desc:    Advent of Code 2024 day 10.
  */
 
+use aoc_common::input::{ArgError, Args, DayInputs, InputName, InputSource};
+use malachite_nz::natural::Natural;
+use petgraph::algo::{toposort, Cycle};
 use petgraph::dot::{Config, Dot};
 use petgraph::{csr::DefaultIx, graph::NodeIndex, stable_graph::StableGraph};
 use std::collections::{HashMap, HashSet};
@@ -36,19 +39,6 @@ where
     }
 }
 
-impl<T, const N_DIMS: usize> VectorN<T, N_DIMS> {
-    /// constructs a new VectorN with the default values
-    fn new() -> Self
-    where
-        T: Copy,
-        T: Default,
-    {
-        Self {
-            coords: [T::default(); N_DIMS],
-        }
-    }
-}
-
 impl<T, const N_DIMS: usize> std::ops::Index<usize> for VectorN<T, N_DIMS> {
     type Output = T;
 
@@ -71,8 +61,8 @@ where
 
     fn add(self, other: Self) -> Self {
         let mut new_coords: [T; N_DIMS] = [T::default(); N_DIMS];
-        for i in 0..N_DIMS {
-            new_coords[i] = self.coords[i] + other.coords[i];
+        for (i, new_coord) in new_coords.iter_mut().enumerate() {
+            *new_coord = self.coords[i] + other.coords[i];
         }
         Self { coords: new_coords }
     }
@@ -88,8 +78,8 @@ where
 
     fn sub(self, other: Self) -> Self {
         let mut new_coords: [T; N_DIMS] = [T::default(); N_DIMS];
-        for i in 0..N_DIMS {
-            new_coords[i] = self.coords[i] - other.coords[i];
+        for (i, new_coord) in new_coords.iter_mut().enumerate() {
+            *new_coord = self.coords[i] - other.coords[i];
         }
         Self { coords: new_coords }
     }
@@ -109,8 +99,8 @@ enum DirectionSteps {
 
 impl DirectionSteps {
     // returns an (x_step, y_step) pair used for iteration
-    fn to_step_vec(&self) -> VectorN<isize, 2> {
-        match *self {
+    fn to_step_vec(self) -> VectorN<isize, 2> {
+        match self {
             DirectionSteps::HorizLeft => [-1, 0],
             DirectionSteps::HorizRight => [1, 0],
             DirectionSteps::VertUp => [0, -1],
@@ -140,45 +130,57 @@ impl DirectionSteps {
     }
 }
 
+/// a map of heights, indexed as [y][x]. impassable positions are None.
+type HeightMap = Vec<Vec<Option<u8>>>;
+
 // I'm on record that I hate this function
-fn hamfist_into_2d_arr<const WIDTH: usize, const HEIGHT: usize>(
-    input: &str,
-) -> Result<[[usize; WIDTH]; HEIGHT], ()> {
-    let mut usize_arr: [[usize; WIDTH]; HEIGHT] = [[0; WIDTH]; HEIGHT];
-    for (idx, line) in input.lines().enumerate() {
-        if line.len() != WIDTH {
+// NOTE: any char that isn't a digit is an impassable position, stored as None
+// NOTE: every line has to be as wide as the first
+fn hamfist_into_2d_arr(input: &str) -> Result<HeightMap, ()> {
+    let mut height_arr: HeightMap = Vec::new();
+    for line in input.lines() {
+        let line_arr: Vec<Option<u8>> = line
+            .chars()
+            .map(|char| char.to_digit(10).map(|digit| digit as u8))
+            .collect();
+        if height_arr
+            .first()
+            .is_some_and(|first| first.len() != line_arr.len())
+        {
             return Err(());
         }
-
-        let line_arr: [usize; WIDTH] = line
-            .chars()
-            .map(|char| char.to_digit(10).unwrap() as usize)
-            .collect::<Vec<usize>>()
-            .try_into()
-            .unwrap();
-        let _ = std::mem::replace(usize_arr.get_mut(idx).ok_or(())?, line_arr);
+        height_arr.push(line_arr);
     }
 
-    // return our newly-constructed char array
-    Ok(usize_arr)
+    // return our newly-constructed height array
+    Ok(height_arr)
+}
+
+// returns the (width, height) of a map
+fn map_size(arr: &HeightMap) -> (usize, usize) {
+    (arr.first().map_or(0, |row| row.len()), arr.len())
 }
 
 // NOTE: points are 0-based
 // NOTE: points provided as (x, y) even though indexes to array are backwards
-fn val_at_point<const WIDTH: usize, const HEIGHT: usize>(
-    arr: &[[usize; WIDTH]; HEIGHT],
-    point: VectorN<usize, 2>,
-) -> usize {
-    let x: usize = point[0].min(WIDTH - 1);
-    let y: usize = point[1].min(HEIGHT - 1);
+fn val_at_point(arr: &HeightMap, point: VectorN<usize, 2>) -> Option<u8> {
+    let (width, height) = map_size(arr);
+    let x: usize = point[0].min(width - 1);
+    let y: usize = point[1].min(height - 1);
     arr[y][x]
 }
 
-fn find_neighbors_with_val<const WIDTH: usize, const HEIGHT: usize>(
-    arr: &[[usize; WIDTH]; HEIGHT],
+/// returns every in-bounds, passable neighbor of point whose value matches
+/// is_wanted
+fn find_neighbors_where<F>(
+    arr: &HeightMap,
     point: VectorN<usize, 2>,
-    search_val: usize,
-) -> Vec<VectorN<usize, 2>> {
+    is_wanted: F,
+) -> Vec<VectorN<usize, 2>>
+where
+    F: Fn(u8) -> bool,
+{
+    let (width, height) = map_size(arr);
     let mut neighbor_positions: Vec<VectorN<usize, 2>> = Vec::new();
     for direction in DirectionSteps::iterate_all() {
         // grab step, check bounds
@@ -197,11 +199,11 @@ fn find_neighbors_with_val<const WIDTH: usize, const HEIGHT: usize>(
             };
 
         // make sure our point is within bounds
-        if cur_point[0] >= WIDTH || cur_point[1] >= HEIGHT {
+        if cur_point[0] >= width || cur_point[1] >= height {
             continue;
         }
 
-        if val_at_point::<WIDTH, HEIGHT>(arr, cur_point) == search_val {
+        if val_at_point(arr, cur_point).is_some_and(&is_wanted) {
             // we found the value we were looking for, so add the position
             neighbor_positions.push(cur_point);
         }
@@ -211,62 +213,121 @@ fn find_neighbors_with_val<const WIDTH: usize, const HEIGHT: usize>(
     neighbor_positions
 }
 
-/// builds a directed graph representing the trail
-fn build_trail_graph<const WIDTH: usize, const HEIGHT: usize>(
-    arr: &[[usize; WIDTH]; HEIGHT],
-    max_val: usize,
-) -> StableGraph<usize, ()> {
-    let mut graph: StableGraph<usize, ()> = StableGraph::new();
-    let mut pos_id_map: HashMap<VectorN<usize, 2>, NodeIndex<DefaultIx>> = HashMap::new();
+/// the rules describing which positions a trail may start at, end at, and step
+/// between
+struct TrailRules {
+    /// returns true if a trail may step from the first height to the second
+    is_step: Box<dyn Fn(u8, u8) -> bool>,
+    /// returns true if a trail may start at the provided height
+    is_start: Box<dyn Fn(u8) -> bool>,
+    /// returns true if a trail ends at the provided height
+    is_end: Box<dyn Fn(u8) -> bool>,
+}
 
-    // check each position, looking for start points with value 0
-    for start_x in 0..WIDTH {
-        for start_y in 0..HEIGHT {
-            let start_pos: VectorN<usize, 2> = [start_x, start_y].into();
+impl TrailRules {
+    /// the puzzle's rules: climb exactly 1 per step, from 0 up to 9
+    fn climb_by_one() -> Self {
+        Self {
+            is_step: Box::new(|from, to| to == from + 1),
+            is_start: Box::new(|height| height == 0),
+            is_end: Box::new(|height| height == 9),
+        }
+    }
+
+    /// climb at most 1 per step, descending as far as you like, from 0 to 9.
+    /// steps between equal heights make the graph cyclic.
+    fn climb_at_most_one() -> Self {
+        Self {
+            is_step: Box::new(|from, to| to <= from + 1),
+            ..Self::climb_by_one()
+        }
+    }
+
+    /// the puzzle's trails walked backwards: descend exactly 1 per step, from
+    /// 9 down to 0
+    fn descend_by_one() -> Self {
+        Self {
+            is_step: Box::new(|from, to| to + 1 == from),
+            is_start: Box::new(|height| height == 9),
+            is_end: Box::new(|height| height == 0),
+        }
+    }
+}
+
+impl std::str::FromStr for TrailRules {
+    type Err = String;
+
+    /// looks up a set of rules by name
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "climb-by-one" => Ok(Self::climb_by_one()),
+            "climb-at-most-one" => Ok(Self::climb_at_most_one()),
+            "descend-by-one" => Ok(Self::descend_by_one()),
+            _ => Err(format!(
+                "unknown trail rules '{}', expected climb-by-one, climb-at-most-one or \
+                 descend-by-one",
+                name
+            )),
+        }
+    }
+}
+
+/// a single position on the trail map, used as the trail graph's node weight
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct TrailNode {
+    pos: VectorN<usize, 2>,
+    height: u8,
+}
 
-            // start at 0
-            if val_at_point(arr, start_pos) != 0 {
+/// builds a directed graph representing every trail allowed by the rules
+fn build_trail_graph(arr: &HeightMap, rules: &TrailRules) -> StableGraph<TrailNode, ()> {
+    let (width, height) = map_size(arr);
+    let mut graph: StableGraph<TrailNode, ()> = StableGraph::new();
+    let mut pos_id_map: HashMap<VectorN<usize, 2>, NodeIndex<DefaultIx>> = HashMap::new();
+    // only passable positions ever make it into the graph
+    let node_at = |pos: VectorN<usize, 2>| TrailNode {
+        pos,
+        height: val_at_point(arr, pos).unwrap(),
+    };
+
+    // check each position, looking for start points
+    for start_x in 0..width {
+        for start_y in 0..height {
+            let start_pos: VectorN<usize, 2> = [start_x, start_y].into();
+            if !val_at_point(arr, start_pos).is_some_and(&rules.is_start) {
                 continue;
             }
 
-            // add the new start position to the graph
-            let node_id = graph.add_node(val_at_point(arr, start_pos));
+            // add the new start position to the graph, unless an earlier trail
+            // already walked through it
+            if pos_id_map.contains_key(&start_pos) {
+                continue;
+            }
+            let node_id = graph.add_node(node_at(start_pos));
             pos_id_map.insert(start_pos, node_id);
 
             let mut visit_queue: Vec<VectorN<usize, 2>> = vec![start_pos];
             while let Some(cur_pos) = visit_queue.pop() {
-                // if the value at the current position is max_val, stop
-                if val_at_point(arr, cur_pos) == max_val {
+                // if the current position ends the trail, stop
+                let cur_val = node_at(cur_pos).height;
+                if (rules.is_end)(cur_val) {
                     continue;
                 }
 
-                // iterate over each neighbor position that contains the next
-                // value in-sequence
+                // iterate over each neighbor position we're allowed to step to
+                let cur_idx = pos_id_map[&cur_pos];
                 for neighbor_pos in
-                    find_neighbors_with_val(arr, cur_pos, val_at_point(arr, cur_pos) + 1)
+                    find_neighbors_where(arr, cur_pos, |val| (rules.is_step)(cur_val, val))
                 {
-                    if pos_id_map.contains_key(&neighbor_pos) {
-                        // if a node already exists for the point we're going to
-                        // add to the queue, we've already walked it. Make an
-                        // edge to it, and don't add it to the queue again.
-                        let cur_idx = pos_id_map.get(&cur_pos).unwrap();
-                        let neighbor_idx = pos_id_map.get(&neighbor_pos).unwrap();
-                        graph.add_edge(*cur_idx, *neighbor_idx, ());
-                    } else {
-                        // if a node hasn't been created yet for the new point,
-                        // add it to the graph and pos_id_map, and add it to the
-                        // visit queue
-                        // add to graph + map
-                        let neighbor_idx = graph.add_node(val_at_point(arr, neighbor_pos));
-                        pos_id_map.insert(neighbor_pos, neighbor_idx);
-
-                        // add edge to graph
-                        let cur_idx = pos_id_map.get(&cur_pos).unwrap().clone();
-                        graph.add_edge(cur_idx, neighbor_idx, ());
-
-                        // add pos to visit_queue
+                    // if a node already exists for the point we're going to add
+                    // to the queue, we've already walked it, so just make an
+                    // edge to it. otherwise, add it to the graph and pos_id_map,
+                    // and add it to the visit queue.
+                    let neighbor_idx = *pos_id_map.entry(neighbor_pos).or_insert_with(|| {
                         visit_queue.push(neighbor_pos);
-                    }
+                        graph.add_node(node_at(neighbor_pos))
+                    });
+                    graph.add_edge(cur_idx, neighbor_idx, ());
                 }
             }
         }
@@ -275,26 +336,24 @@ fn build_trail_graph<const WIDTH: usize, const HEIGHT: usize>(
     graph
 }
 
-/// returns the number of nodes with the provided weight that are reachable from
-/// the provided start node
-fn n_reachable_nodes_with_weight<T>(
+/// returns the number of nodes matching is_target that are reachable from the
+/// provided start node
+fn n_reachable_nodes_where<T, F>(
     graph: &StableGraph<T, ()>,
     idx: NodeIndex<DefaultIx>,
-    weight: T,
+    is_target: F,
 ) -> usize
 where
-    T: Copy,
-    T: PartialEq,
+    F: Fn(&T) -> bool,
 {
     let mut count: usize = 0;
     let mut visited_idx_set: HashSet<NodeIndex<DefaultIx>> = HashSet::new();
     let mut visit_queue: Vec<NodeIndex<DefaultIx>> = vec![idx];
     while let Some(cur_idx) = visit_queue.pop() {
-        if visited_idx_set.contains(&cur_idx) {
+        if !visited_idx_set.insert(cur_idx) {
             continue;
         }
-        visited_idx_set.insert(cur_idx);
-        if graph.node_weight(cur_idx).is_some_and(|w| *w == weight) {
+        if graph.node_weight(cur_idx).is_some_and(&is_target) {
             count += 1;
         } else {
             for neighbor in graph.neighbors(cur_idx) {
@@ -306,74 +365,381 @@ where
     count
 }
 
-/// returns the number of nodes with the provided weight that are reachable from
-/// the provided start node
-fn n_paths_to_nodes_with_weight<T>(
+/// returns the number of distinct paths from every node to any node matching
+/// is_target, stopping at the first match along each path. each node is only
+/// visited once, in reverse topological order, so this fails if the graph has
+/// a cycle.
+fn n_paths_to_nodes_where<T, F>(
     graph: &StableGraph<T, ()>,
-    idx: NodeIndex<DefaultIx>,
-    weight: T,
-) -> usize
+    is_target: F,
+) -> Result<HashMap<NodeIndex<DefaultIx>, Natural>, Cycle<NodeIndex<DefaultIx>>>
 where
-    T: Copy,
-    T: PartialEq,
+    F: Fn(&T) -> bool,
 {
-    let mut count: usize = 0;
-    let mut visit_queue: Vec<NodeIndex<DefaultIx>> = vec![idx];
-    while let Some(cur_idx) = visit_queue.pop() {
-        if graph.node_weight(cur_idx).is_some_and(|w| *w == weight) {
-            count += 1;
+    let mut path_counts: HashMap<NodeIndex<DefaultIx>, Natural> = HashMap::new();
+    for idx in toposort(graph, None)?.into_iter().rev() {
+        // every successor has already been counted, so just sum them up
+        let mut count: Natural = Natural::from(0u32);
+        if graph.node_weight(idx).is_some_and(&is_target) {
+            count = Natural::from(1u32);
         } else {
-            for neighbor in graph.neighbors(cur_idx) {
-                visit_queue.push(neighbor);
+            for neighbor in graph.neighbors(idx) {
+                count += &path_counts[&neighbor];
             }
         }
+        path_counts.insert(idx, count);
     }
 
-    count
+    Ok(path_counts)
+}
+
+/// the score and rating of a single trailhead. the rating is only known if
+/// the trail graph is acyclic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TrailheadSummary {
+    pos: VectorN<usize, 2>,
+    score: usize,
+    rating: Option<Natural>,
+}
+
+/// returns a summary of every trailhead in the graph, ordered by position
+fn summarise_trailheads(
+    graph: &StableGraph<TrailNode, ()>,
+    rules: &TrailRules,
+    path_counts: Option<&HashMap<NodeIndex<DefaultIx>, Natural>>,
+) -> Vec<TrailheadSummary> {
+    let mut summaries: Vec<TrailheadSummary> = graph
+        .node_indices()
+        .filter(|idx| (rules.is_start)(graph[*idx].height))
+        .map(|idx| TrailheadSummary {
+            pos: graph[idx].pos,
+            score: n_reachable_nodes_where(graph, idx, |node| (rules.is_end)(node.height)),
+            rating: path_counts.map(|counts| counts[&idx].clone()),
+        })
+        .collect();
+    summaries.sort_by_key(|summary| (summary.pos[1], summary.pos[0]));
+
+    summaries
+}
+
+/// returns the total score and, if every trailhead has one, the total rating
+fn total_score_and_rating(summaries: &[TrailheadSummary]) -> (usize, Option<Natural>) {
+    let total_score: usize = summaries.iter().map(|summary| summary.score).sum();
+    let mut total_rating: Option<Natural> = Some(Natural::from(0u32));
+    for summary in summaries.iter() {
+        total_rating = total_rating
+            .zip(summary.rating.as_ref())
+            .map(|(total, rating)| total + rating);
+    }
+
+    (total_score, total_rating)
+}
+
+/// returns the positions along every distinct trail from the provided start
+/// node to a node matching is_end. trails never revisit a position, so this
+/// terminates even if the graph has a cycle.
+fn enumerate_trails<F>(
+    graph: &StableGraph<TrailNode, ()>,
+    start_idx: NodeIndex<DefaultIx>,
+    is_end: F,
+) -> Vec<Vec<VectorN<usize, 2>>>
+where
+    F: Fn(&TrailNode) -> bool,
+{
+    let mut trails: Vec<Vec<VectorN<usize, 2>>> = Vec::new();
+
+    // each entry in the queue holds the full path walked to reach its last node
+    let mut visit_queue: Vec<Vec<NodeIndex<DefaultIx>>> = vec![vec![start_idx]];
+    while let Some(cur_path) = visit_queue.pop() {
+        let cur_idx = *cur_path.last().unwrap();
+        if is_end(&graph[cur_idx]) {
+            trails.push(cur_path.iter().map(|idx| graph[*idx].pos).collect());
+            continue;
+        }
+
+        for neighbor in graph.neighbors(cur_idx) {
+            if !cur_path.contains(&neighbor) {
+                let mut next_path = cur_path.clone();
+                next_path.push(neighbor);
+                visit_queue.push(next_path);
+            }
+        }
+    }
+
+    // order the trails by position along the way, so the output is stable
+    trails.sort_by_key(|trail| {
+        trail
+            .iter()
+            .map(|pos| (pos[1], pos[0]))
+            .collect::<Vec<(usize, usize)>>()
+    });
+    trails
+}
+
+/// parses an "x,y" position, like the 2,0 in `--trails 2,0`
+fn parse_position(pos_str: &str) -> Result<VectorN<usize, 2>, String> {
+    let parse_coord = |coord_str: &str| {
+        coord_str
+            .trim()
+            .parse::<usize>()
+            .map_err(|e| format!("invalid coordinate '{}': {}", coord_str, e))
+    };
+    match pos_str.split_once(',') {
+        Some((x, y)) => Ok([parse_coord(x)?, parse_coord(y)?].into()),
+        None => Err(String::from("expected an x,y position, e.g. 2,0")),
+    }
+}
+
+/// renders the map in the puzzle's format, with every position not on one of
+/// the provided trails replaced by '.'
+fn render_trails(arr: &HeightMap, trails: &[Vec<VectorN<usize, 2>>]) -> String {
+    let on_trail: HashSet<VectorN<usize, 2>> = trails.iter().flatten().copied().collect();
+
+    let mut rendered = String::new();
+    for (y, row) in arr.iter().enumerate() {
+        for (x, val) in row.iter().enumerate() {
+            match val {
+                Some(val) if on_trail.contains(&[x, y].into()) => {
+                    rendered.push_str(&val.to_string())
+                }
+                _ => rendered.push('.'),
+            }
+        }
+        rendered.push('\n');
+    }
+
+    rendered
 }
 
 /// the entrypoint
 fn main() {
-    // these have to be constant due to a compiler bug.
-    // test values
-    //const WIDTH: usize = 8;
-    //const HEIGHT: usize = 8;
-    // final values
-    const WIDTH: usize = 53;
-    const HEIGHT: usize = 53;
+    // parse the optional arguments, e.g. `day_10 --rules descend-by-one` or
+    // `day_10 --input example --breakdown --trails 2,0`
+    let mut rules: TrailRules = TrailRules::climb_by_one();
+    let mut breakdown: bool = false;
+    let mut trails_from: Option<VectorN<usize, 2>> = None;
+    let mut args = Args::from_env();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rules" => rules = args.value(&arg),
+            "--breakdown" => breakdown = true,
+            "--trails" => trails_from = Some(args.value_with(&arg, parse_position)),
+            _ => ArgError::Unknown(arg).exit(),
+        }
+    }
 
     // metadata
-    let input: String = std::fs::read_to_string("input.txt").unwrap();
-    println!(
-        "width: {} - height: {}",
-        input.lines().next().unwrap().len(),
-        input.lines().count(),
-    );
+    let source: InputSource = args.source().clone();
+    let inputs = DayInputs::new(10, env!("CARGO_MANIFEST_DIR"))
+        .embed(InputName::Example(1), include_str!("../test-input.txt"));
+    let input: String = inputs.load_or_exit(&source);
+    let width: usize = input.lines().next().unwrap().len();
+    let height: usize = input.lines().count();
+    println!("width: {} - height: {}", width, height);
 
     // part 1
-    let hamfist_array: [[usize; WIDTH]; HEIGHT] =
-        hamfist_into_2d_arr::<WIDTH, HEIGHT>(&input).unwrap();
-    let trail_graph = build_trail_graph::<WIDTH, HEIGHT>(&hamfist_array, 9);
+    let hamfist_array: HeightMap = match hamfist_into_2d_arr(&input) {
+        Ok(arr) => arr,
+        Err(()) => {
+            eprintln!("every line of the map has to be the same width");
+            std::process::exit(1);
+        }
+    };
+    let trail_graph = build_trail_graph(&hamfist_array, &rules);
     println!(
         "{:?}",
         Dot::with_config(&trail_graph, &[Config::EdgeNoLabel])
     );
 
-    let mut total_score: usize = 0;
-    for idx in trail_graph.node_indices() {
-        if trail_graph.node_weight(idx).is_some_and(|w| *w == 0) {
-            let n_reachable = n_reachable_nodes_with_weight(&trail_graph, idx, 9);
-            total_score += n_reachable;
+    // count the paths from every node once, then break them down by trailhead.
+    // paths can only be counted if the rules don't allow walking in circles.
+    let path_counts = n_paths_to_nodes_where(&trail_graph, |node| (rules.is_end)(node.height)).ok();
+    let summaries = summarise_trailheads(&trail_graph, &rules, path_counts.as_ref());
+    if breakdown {
+        for summary in summaries.iter() {
+            println!(
+                "trailhead ({}, {}) - score: {} - rating: {:?}",
+                summary.pos[0], summary.pos[1], summary.score, summary.rating
+            );
         }
     }
+
+    let (total_score, total_rating) = total_score_and_rating(&summaries);
     println!("Part 1: {}", &total_score);
+    inputs.report_answer(&source, 1, &total_score);
+    match total_rating {
+        Some(total_rating) => {
+            println!("Part 2: {}", &total_rating);
+            inputs.report_answer(&source, 2, &total_rating);
+        }
+        None => println!("Part 2: the trail graph has a cycle, so it has no rating"),
+    }
 
-    total_score = 0;
-    for idx in trail_graph.node_indices() {
-        if trail_graph.node_weight(idx).is_some_and(|w| *w == 0) {
-            let n_reachable = n_paths_to_nodes_with_weight(&trail_graph, idx, 9);
-            total_score += n_reachable;
+    // optionally list and draw every trail from a single trailhead
+    if let Some(start_pos) = trails_from {
+        let start_idx = match trail_graph.node_indices().find(|idx| {
+            trail_graph[*idx].pos == start_pos && (rules.is_start)(trail_graph[*idx].height)
+        }) {
+            Some(start_idx) => start_idx,
+            None => {
+                eprintln!("no trailhead at ({}, {})", start_pos[0], start_pos[1]);
+                std::process::exit(1);
+            }
+        };
+
+        let trails = enumerate_trails(&trail_graph, start_idx, |node| (rules.is_end)(node.height));
+        for trail in trails.iter() {
+            let trail_strs: Vec<String> = trail
+                .iter()
+                .map(|pos| format!("({}, {})", pos[0], pos[1]))
+                .collect();
+            println!("{}", trail_strs.join(" -> "));
         }
+        println!(
+            "{} trails from ({}, {})",
+            trails.len(),
+            start_pos[0],
+            start_pos[1]
+        );
+        print!("{}", render_trails(&hamfist_array, &trails));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // returns the total score and rating of a map under the puzzle's rules
+    fn solve_map(input: &str) -> (usize, Option<Natural>) {
+        solve_map_with(input, &TrailRules::climb_by_one())
+    }
+
+    // returns the total score and rating of a map under the provided rules
+    fn solve_map_with(input: &str, rules: &TrailRules) -> (usize, Option<Natural>) {
+        let trail_graph = build_trail_graph(&hamfist_into_2d_arr(input).unwrap(), rules);
+        let path_counts =
+            n_paths_to_nodes_where(&trail_graph, |node| (rules.is_end)(node.height)).ok();
+        total_score_and_rating(&summarise_trailheads(
+            &trail_graph,
+            rules,
+            path_counts.as_ref(),
+        ))
+    }
+
+    #[test]
+    fn example_input() {
+        assert_eq!(
+            solve_map(include_str!("../test-input.txt")),
+            (36, Some(Natural::from(81u32)))
+        );
+    }
+
+    #[test]
+    fn impassable_example_maps() {
+        // the smaller examples are 7x7, and use '.' for impassable positions
+        let forked: &str = "...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9\n";
+        assert_eq!(solve_map(forked), (2, Some(Natural::from(2u32))));
+        let rated: &str = ".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....\n";
+        assert_eq!(solve_map(rated).1, Some(Natural::from(3u32)));
+    }
+
+    #[test]
+    fn other_rules() {
+        let input: &str = include_str!("../test-input.txt");
+        let rules = |name: &str| name.parse::<TrailRules>().unwrap();
+
+        // walking every trail backwards finds the same pairs of ends, and the
+        // same trails between them
+        assert_eq!(
+            solve_map_with(input, &rules("descend-by-one")),
+            solve_map_with(input, &rules("climb-by-one"))
+        );
+
+        // stepping back down onto a height you just climbed from walks in a
+        // circle, so the trails can't be counted
+        let (_, rating) = solve_map_with(input, &rules("climb-at-most-one"));
+        assert_eq!(rating, None);
+
+        assert_eq!(
+            "climb-by-two".parse::<TrailRules>().err(),
+            Some(String::from(
+                "unknown trail rules 'climb-by-two', expected climb-by-one, \
+                 climb-at-most-one or descend-by-one"
+            ))
+        );
+    }
+
+    #[test]
+    fn trails_match_ratings() {
+        let input: &str = include_str!("../test-input.txt");
+        let rules: TrailRules = TrailRules::climb_by_one();
+        let trail_graph = build_trail_graph(&hamfist_into_2d_arr(input).unwrap(), &rules);
+        let path_counts =
+            n_paths_to_nodes_where(&trail_graph, |node| (rules.is_end)(node.height)).unwrap();
+        for summary in summarise_trailheads(&trail_graph, &rules, Some(&path_counts)) {
+            let start_idx = trail_graph
+                .node_indices()
+                .find(|idx| trail_graph[*idx].pos == summary.pos)
+                .unwrap();
+            let trails =
+                enumerate_trails(&trail_graph, start_idx, |node| (rules.is_end)(node.height));
+            assert_eq!(
+                summary.rating,
+                Some(Natural::from(trails.len())),
+                "{:?}",
+                summary.pos
+            );
+        }
+    }
+
+    #[test]
+    fn rendered_trails() {
+        // the right-hand branch never reaches a 9
+        let input: &str = "...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....0\n";
+        let arr: HeightMap = hamfist_into_2d_arr(input).unwrap();
+        let rules: TrailRules = TrailRules::climb_by_one();
+        let trail_graph = build_trail_graph(&arr, &rules);
+        let start_idx = trail_graph
+            .node_indices()
+            .find(|idx| trail_graph[*idx].height == 0 && trail_graph[*idx].pos[1] == 0)
+            .unwrap();
+        let trails = enumerate_trails(&trail_graph, start_idx, |node| (rules.is_end)(node.height));
+        let expected: Vec<VectorN<usize, 2>> = [
+            [3, 0],
+            [3, 1],
+            [3, 2],
+            [3, 3],
+            [2, 3],
+            [1, 3],
+            [0, 3],
+            [0, 4],
+            [0, 5],
+            [0, 6],
+        ]
+        .into_iter()
+        .map(VectorN::from)
+        .collect();
+        assert_eq!(trails, [expected]);
+        assert_eq!(
+            render_trails(&arr, &trails),
+            "...0...\n...1...\n...2...\n6543...\n7......\n8......\n9......\n"
+        );
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(parse_position("2,0"), Ok([2, 0].into()));
+        assert_eq!(parse_position(" 2 , 10 "), Ok([2, 10].into()));
+        assert_eq!(
+            parse_position("2"),
+            Err(String::from("expected an x,y position, e.g. 2,0"))
+        );
+        assert_eq!(
+            parse_position("2,-1"),
+            Err(String::from(
+                "invalid coordinate '-1': invalid digit found in string"
+            ))
+        );
+        assert!(parse_position("2,0,1").is_err());
     }
-    println!("Part 2: {}", &total_score);
 }
@@ -0,0 +1,422 @@
+/* file:    exact.rs
+ * author:  garnt
+ * date:    01/02/2025
+ * desc:    Exact integer solver for day 13 claw machines.
+ */
+
+use crate::{Problem, SolveConfig};
+use std::fmt;
+
+/// an intermediate value in the solver didn't fit in an i128
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Overflow(pub &'static str);
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} doesn't fit in an i128", self.0)
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// why a problem's prize can't be won
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Unsolvable {
+    /// the buttons can't move the claw onto the prize's position at all
+    Unreachable,
+    /// reaching the prize needs a fractional number of presses
+    NonInteger,
+    /// reaching the prize needs a negative number of presses
+    NegativePresses,
+    /// reaching the prize needs more presses than a button's cap allows
+    ExceedsCap,
+}
+
+impl fmt::Display for Unsolvable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason: &str = match self {
+            Unsolvable::Unreachable => "the prize isn't in line with the buttons",
+            Unsolvable::NonInteger => "needs a fractional number of presses",
+            Unsolvable::NegativePresses => "needs a negative number of presses",
+            Unsolvable::ExceedsCap => "needs more presses than allowed",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// the result of trying to solve a single problem
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// the cheapest way to win the prize
+    Solved {
+        a: i128,
+        b: i128,
+        tokens: i128,
+    },
+    Unsolvable(Unsolvable),
+}
+
+// returns a * b, naming the value being calculated if it overflows
+fn mul(a: i128, b: i128, what: &'static str) -> Result<i128, Overflow> {
+    a.checked_mul(b).ok_or(Overflow(what))
+}
+
+// returns a + b, naming the value being calculated if it overflows
+fn add(a: i128, b: i128, what: &'static str) -> Result<i128, Overflow> {
+    a.checked_add(b).ok_or(Overflow(what))
+}
+
+// returns a - b, naming the value being calculated if it overflows
+fn sub(a: i128, b: i128, what: &'static str) -> Result<i128, Overflow> {
+    a.checked_sub(b).ok_or(Overflow(what))
+}
+
+/// returns (g, x, y) such that a*x + b*y == g, where g = gcd(a, b) >= 0. the
+/// coefficients never grow larger than |a| or |b|, so this can't overflow
+/// unless a or b is i128::MIN.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+// integer division rounding towards positive infinity, for a positive divisor
+fn div_ceil(a: i128, b: i128) -> i128 {
+    a.div_euclid(b) + i128::from(a.rem_euclid(b) != 0)
+}
+
+/// narrows the range of k for which lo <= base + k * step <= hi, where a hi of
+/// None is unbounded
+fn narrow_k_range(
+    k_range: (Option<i128>, Option<i128>),
+    base: i128,
+    step: i128,
+    lo: i128,
+    hi: Option<i128>,
+) -> Result<(Option<i128>, Option<i128>), Overflow> {
+    let (mut k_min, mut k_max) = k_range;
+    let mut tighten_min = |bound: i128| k_min = Some(k_min.map_or(bound, |k| k.max(bound)));
+    let mut tighten_max = |bound: i128| k_max = Some(k_max.map_or(bound, |k| k.min(bound)));
+    let lo_gap: i128 = sub(lo, base, "press count bound")?;
+    let hi_gap: Option<i128> = hi
+        .map(|hi| sub(hi, base, "press count bound"))
+        .transpose()?;
+    if step > 0 {
+        tighten_min(div_ceil(lo_gap, step));
+        if let Some(hi_gap) = hi_gap {
+            tighten_max(hi_gap.div_euclid(step));
+        }
+    } else if step < 0 {
+        // flip both sides over so the step is positive
+        tighten_max((-lo_gap).div_euclid(-step));
+        if let Some(hi_gap) = hi_gap {
+            tighten_min(div_ceil(-hi_gap, -step));
+        }
+    }
+
+    Ok((k_min, k_max))
+}
+
+/// makes sure presses is a valid number of presses for the provided button
+fn check_presses(presses: i128, button: usize, config: &SolveConfig) -> Result<(), Unsolvable> {
+    if presses < 0 {
+        Err(Unsolvable::NegativePresses)
+    } else if config.max_presses[button].is_some_and(|max| presses > max) {
+        Err(Unsolvable::ExceedsCap)
+    } else {
+        Ok(())
+    }
+}
+
+// returns true if the range of k is empty
+fn is_empty_range(k_range: (Option<i128>, Option<i128>)) -> bool {
+    matches!(k_range, (Some(k_min), Some(k_max)) if k_min > k_max)
+}
+
+// press counts, or the reason there aren't any, or an overflow
+type PressesResult = Result<Result<(i128, i128), Unsolvable>, Overflow>;
+
+/// returns the cheapest non-negative (a, b) with a * p + b * q == t, within
+/// the config's press limits. p and q must not both be 0.
+fn solve_one_equation(p: i128, q: i128, t: i128, config: &SolveConfig) -> PressesResult {
+    // if one of the buttons doesn't move the claw along this axis, pressing it
+    // can only cost tokens, so never press it
+    if q == 0 || p == 0 {
+        let (button, step) = if q == 0 { (0, p) } else { (1, q) };
+        if t % step != 0 {
+            return Ok(Err(Unsolvable::NonInteger));
+        }
+        let presses: i128 = t / step;
+        return Ok(check_presses(presses, button, config).map(|_| {
+            if button == 0 {
+                (presses, 0)
+            } else {
+                (0, presses)
+            }
+        }));
+    }
+
+    // find any integer solution, then describe every other one in terms of k:
+    // a = a0 + k * a_step, b = b0 + k * b_step
+    let (g, x, y) = extended_gcd(p, q);
+    if t % g != 0 {
+        return Ok(Err(Unsolvable::NonInteger));
+    }
+    let a0: i128 = mul(x, t / g, "particular solution")?;
+    let b0: i128 = mul(y, t / g, "particular solution")?;
+    let (a_step, b_step) = (q / g, -p / g);
+
+    // limit k to non-negative press counts, then to the counts we're allowed
+    // to use, so we can tell which limit made it impossible
+    let mut k_range = (None, None);
+    k_range = narrow_k_range(k_range, a0, a_step, 0, None)?;
+    k_range = narrow_k_range(k_range, b0, b_step, 0, None)?;
+    if is_empty_range(k_range) {
+        return Ok(Err(Unsolvable::NegativePresses));
+    }
+    let [a_max, b_max] = config.max_presses;
+    k_range = narrow_k_range(k_range, a0, a_step, 0, a_max)?;
+    k_range = narrow_k_range(k_range, b0, b_step, 0, b_max)?;
+    if is_empty_range(k_range) {
+        return Ok(Err(Unsolvable::ExceedsCap));
+    }
+
+    // the cost is linear in k, so the cheapest solution is at one end of the
+    // range. the cost never drops below 0, so the end we want is bounded.
+    let [a_cost, b_cost] = config.token_costs;
+    let cost_slope: i128 = add(
+        mul(a_cost, a_step, "cost slope")?,
+        mul(b_cost, b_step, "cost slope")?,
+        "cost slope",
+    )?;
+    let k = match (cost_slope.signum(), k_range) {
+        (1, (Some(k_min), _)) | (0, (Some(k_min), _)) => k_min,
+        (-1, (_, Some(k_max))) | (0, (None, Some(k_max))) => k_max,
+        _ => return Ok(Err(Unsolvable::NegativePresses)),
+    };
+
+    Ok(Ok((
+        add(a0, mul(k, a_step, "A presses")?, "A presses")?,
+        add(b0, mul(k, b_step, "B presses")?, "B presses")?,
+    )))
+}
+
+/// returns the cheapest non-negative number of (a, b) presses that reaches the
+/// problem's target, offset and limited as described by the config
+pub fn solve_presses(problem: &Problem, config: &SolveConfig) -> PressesResult {
+    let [ax, ay] = problem.a_values;
+    let [bx, by] = problem.b_values;
+    let tx: i128 = add(problem.target[0], config.target_offset, "offset prize X")?;
+    let ty: i128 = add(problem.target[1], config.target_offset, "offset prize Y")?;
+
+    // if the buttons move the claw in different directions, there's exactly
+    // one solution, given by Cramer's rule
+    let cross = |a: i128, b: i128, c: i128, d: i128, what: &'static str| {
+        sub(mul(a, b, what)?, mul(c, d, what)?, what)
+    };
+    let det: i128 = cross(ax, by, ay, bx, "determinant")?;
+    if det != 0 {
+        let a_num: i128 = cross(tx, by, ty, bx, "A numerator")?;
+        let b_num: i128 = cross(ax, ty, ay, tx, "B numerator")?;
+        if a_num % det != 0 || b_num % det != 0 {
+            return Ok(Err(Unsolvable::NonInteger));
+        }
+        let (a, b) = (a_num / det, b_num / det);
+        return Ok(check_presses(a, 0, config)
+            .and_then(|_| check_presses(b, 1, config))
+            .map(|_| (a, b)));
+    }
+
+    // otherwise, both buttons move the claw along the same line, so the
+    // target has to be on it too. that leaves one equation to satisfy.
+    if (ax, bx) != (0, 0) {
+        if cross(ay, tx, ax, ty, "colinearity check")? != 0
+            || cross(by, tx, bx, ty, "colinearity check")? != 0
+        {
+            return Ok(Err(Unsolvable::Unreachable));
+        }
+        solve_one_equation(ax, bx, tx, config)
+    } else if (ay, by) != (0, 0) {
+        if tx != 0 {
+            return Ok(Err(Unsolvable::Unreachable));
+        }
+        solve_one_equation(ay, by, ty, config)
+    } else if tx == 0 && ty == 0 {
+        // neither button moves the claw at all, but it's already there
+        Ok(Ok((0, 0)))
+    } else {
+        Ok(Err(Unsolvable::Unreachable))
+    }
+}
+
+/// returns the cheapest way to win the problem's prize within the config's
+/// limits, or why it can't be won
+pub fn solve_outcome(problem: &Problem, config: &SolveConfig) -> Result<Outcome, Overflow> {
+    let (a, b) = match solve_presses(problem, config)? {
+        Ok(presses) => presses,
+        Err(reason) => return Ok(Outcome::Unsolvable(reason)),
+    };
+
+    let a_tokens: i128 = mul(config.token_costs[0], a, "token cost")?;
+    let b_tokens: i128 = mul(config.token_costs[1], b, "token cost")?;
+    let tokens: i128 = add(a_tokens, b_tokens, "token cost")?;
+    Ok(Outcome::Solved { a, b, tokens })
+}
+
+/// returns the number of tokens needed to win the problem's prize, if it can
+/// be won within the config's limits
+pub fn solve_single_problem(
+    problem: &Problem,
+    config: &SolveConfig,
+) -> Result<Option<i128>, Overflow> {
+    Ok(match solve_outcome(problem, config)? {
+        Outcome::Solved { tokens, .. } => Some(tokens),
+        Outcome::Unsolvable(_) => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Unsolvable::*;
+    use super::*;
+
+    // a problem with the provided buttons and prize
+    fn problem(a_values: [i128; 2], b_values: [i128; 2], target: [i128; 2]) -> Problem {
+        Problem {
+            target,
+            a_values,
+            b_values,
+        }
+    }
+
+    fn solved(a: i128, b: i128, tokens: i128) -> Result<Outcome, Overflow> {
+        Ok(Outcome::Solved { a, b, tokens })
+    }
+
+    fn unsolvable(reason: Unsolvable) -> Result<Outcome, Overflow> {
+        Ok(Outcome::Unsolvable(reason))
+    }
+
+    #[test]
+    fn outcomes() {
+        let part_1: SolveConfig = SolveConfig::part_1();
+        let uncapped = SolveConfig {
+            max_presses: [None; 2],
+            ..part_1
+        };
+        let cases = [
+            // independent buttons
+            (
+                problem([94, 34], [22, 67], [8400, 5400]),
+                part_1,
+                solved(80, 40, 280),
+            ),
+            (
+                problem([2, 0], [0, 2], [3, 4]),
+                part_1,
+                unsolvable(NonInteger),
+            ),
+            (
+                problem([1, 0], [0, 1], [-1, 5]),
+                part_1,
+                unsolvable(NegativePresses),
+            ),
+            (
+                problem([1, 0], [0, 1], [101, 5]),
+                part_1,
+                unsolvable(ExceedsCap),
+            ),
+            (
+                problem([1, 0], [0, 1], [101, 5]),
+                uncapped,
+                solved(101, 5, 308),
+            ),
+            // colinear buttons, where B moves further per token
+            (problem([1, 1], [2, 2], [10, 10]), part_1, solved(0, 5, 5)),
+            (problem([1, 1], [2, 2], [11, 11]), part_1, solved(1, 5, 8)),
+            // colinear buttons, where A moves further per token
+            (problem([4, 4], [1, 1], [8, 8]), part_1, solved(2, 0, 6)),
+            (problem([4, 4], [1, 1], [10, 10]), part_1, solved(2, 2, 8)),
+            // colinear buttons that can only reach the prize together
+            (problem([2, 2], [3, 3], [7, 7]), part_1, solved(2, 1, 7)),
+            (
+                problem([1, 1], [2, 2], [3, 4]),
+                part_1,
+                unsolvable(Unreachable),
+            ),
+            (
+                problem([2, 2], [4, 4], [3, 3]),
+                part_1,
+                unsolvable(NonInteger),
+            ),
+            (
+                problem([1, 1], [2, 2], [-2, -2]),
+                part_1,
+                unsolvable(NegativePresses),
+            ),
+            (
+                problem([1, 1], [2, 2], [400, 400]),
+                part_1,
+                unsolvable(ExceedsCap),
+            ),
+            (
+                problem([1, 1], [2, 2], [400, 400]),
+                uncapped,
+                solved(0, 200, 200),
+            ),
+            // colinear buttons along the Y axis
+            (problem([0, 3], [0, 1], [0, 7]), part_1, solved(0, 7, 7)),
+            (
+                problem([0, 3], [0, 1], [1, 7]),
+                part_1,
+                unsolvable(Unreachable),
+            ),
+            // buttons that don't move the claw at all
+            (problem([0, 0], [2, 3], [4, 6]), part_1, solved(0, 2, 2)),
+            (problem([3, 1], [0, 0], [6, 2]), part_1, solved(2, 0, 6)),
+            (
+                problem([0, 0], [2, 3], [5, 6]),
+                part_1,
+                unsolvable(Unreachable),
+            ),
+            (
+                problem([0, 0], [2, 2], [3, 3]),
+                part_1,
+                unsolvable(NonInteger),
+            ),
+            (
+                problem([0, 0], [2, 3], [-4, -6]),
+                part_1,
+                unsolvable(NegativePresses),
+            ),
+            (problem([0, 0], [0, 0], [0, 0]), part_1, solved(0, 0, 0)),
+            (
+                problem([0, 0], [0, 0], [1, 0]),
+                part_1,
+                unsolvable(Unreachable),
+            ),
+            // values too big to work with
+            (
+                problem([i128::MAX, 0], [0, 2], [1, 1]),
+                part_1,
+                Err(Overflow("determinant")),
+            ),
+            (
+                problem([1, 0], [0, 1], [i128::MAX, 0]),
+                SolveConfig::part_2(),
+                Err(Overflow("offset prize X")),
+            ),
+            (
+                problem([1, 0], [0, 1], [i128::MAX / 2, 0]),
+                uncapped,
+                Err(Overflow("token cost")),
+            ),
+        ];
+        for (problem, config, expected) in cases {
+            assert_eq!(solve_outcome(&problem, &config), expected, "{:?}", problem);
+        }
+    }
+}
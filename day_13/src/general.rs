@@ -0,0 +1,384 @@
+/* file:    general.rs
+ * author:  garnt
+ * date:    01/02/2025
+ * desc:    Claw machines with any number of buttons, in any number of
+ *          dimensions.
+ */
+
+use crate::exact::Overflow;
+use crate::{Problem, SolveConfig};
+use std::fmt;
+
+/// the largest number of press combinations the exact solver will try
+const MAX_SEARCH_SIZE: u128 = 10_000_000;
+
+/// a single button on a generalised claw machine
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Button {
+    pub label: char,
+    /// how far one press moves the claw along each dimension, which may be
+    /// negative
+    pub offsets: Vec<i128>,
+    /// the number of tokens one press costs
+    pub cost: i128,
+    /// the most times the button may be pressed, if limited
+    pub max_presses: Option<i128>,
+}
+
+/// a claw machine with any number of buttons, in any number of dimensions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GeneralProblem {
+    pub target: Vec<i128>,
+    pub buttons: Vec<Button>,
+}
+
+/// why the exact solver couldn't decide a problem
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    Overflow(Overflow),
+    /// a button doesn't have one offset per target dimension
+    WrongDimensions(char),
+    /// a button could be pressed any number of times without overshooting, so
+    /// there's no way to tell how many presses to try
+    Unbounded(char),
+    /// there are more press combinations than the solver is willing to try
+    SearchTooLarge(u128),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Overflow(e) => write!(f, "{}", e),
+            SolveError::WrongDimensions(label) => {
+                write!(f, "button {} has the wrong number of offsets", label)
+            }
+            SolveError::Unbounded(label) => {
+                write!(f, "button {} has no limit on its presses", label)
+            }
+            SolveError::SearchTooLarge(size) => {
+                write!(f, "{} press combinations is too many to try", size)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<Overflow> for SolveError {
+    fn from(e: Overflow) -> Self {
+        SolveError::Overflow(e)
+    }
+}
+
+impl GeneralProblem {
+    /// builds the general form of a two-button problem, with the config's
+    /// offset already applied to the target
+    pub fn from_problem(problem: &Problem, config: &SolveConfig) -> Result<Self, Overflow> {
+        let button = |label: char, offsets: [i128; 2], idx: usize| Button {
+            label,
+            offsets: offsets.to_vec(),
+            cost: config.token_costs[idx],
+            max_presses: config.max_presses[idx],
+        };
+
+        Self {
+            target: vec![problem.target[0], problem.target[1]],
+            buttons: vec![
+                button('A', problem.a_values, 0),
+                button('B', problem.b_values, 1),
+            ],
+        }
+        .with_limits(None, config.target_offset)
+    }
+
+    /// returns a copy of the problem with target_offset added to every
+    /// dimension of the target, and every button capped at max_presses on top
+    /// of any cap it already has
+    pub fn with_limits(
+        &self,
+        max_presses: Option<i128>,
+        target_offset: i128,
+    ) -> Result<Self, Overflow> {
+        let target: Vec<i128> = self
+            .target
+            .iter()
+            .map(|val| {
+                val.checked_add(target_offset)
+                    .ok_or(Overflow("offset prize"))
+            })
+            .collect::<Result<_, _>>()?;
+        let buttons: Vec<Button> = self
+            .buttons
+            .iter()
+            .map(|button| Button {
+                max_presses: match (button.max_presses, max_presses) {
+                    (Some(own), Some(max)) => Some(own.min(max)),
+                    (own, max) => own.or(max),
+                },
+                ..button.clone()
+            })
+            .collect();
+        Ok(Self { target, buttons })
+    }
+
+    /// returns the most times a button could be pressed, either because of its
+    /// cap or because some dimension only ever moves the claw forwards and the
+    /// button would overshoot the target along it
+    fn press_bound(&self, button_idx: usize) -> Option<i128> {
+        let button: &Button = &self.buttons[button_idx];
+        let overshoot_bound: Option<i128> = (0..self.target.len())
+            .filter(|dim| {
+                button.offsets[*dim] > 0
+                    && self.buttons.iter().all(|other| other.offsets[*dim] >= 0)
+            })
+            .map(|dim| self.target[dim].div_euclid(button.offsets[dim]))
+            .min();
+        match (button.max_presses, overshoot_bound) {
+            (Some(max), Some(bound)) => Some(max.min(bound)),
+            (max, bound) => max.or(bound),
+        }
+    }
+}
+
+// returns a * b - c * d
+fn cross(a: i128, b: i128, c: i128, d: i128) -> Result<i128, Overflow> {
+    let overflow = Overflow("elimination step");
+    a.checked_mul(b)
+        .zip(c.checked_mul(d))
+        .and_then(|(ab, cd)| ab.checked_sub(cd))
+        .ok_or(overflow)
+}
+
+// returns the greatest common divisor of a and b, always non-negative
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a as i128
+}
+
+/// a system of equations, one row per dimension, whose columns are the
+/// buttons' press counts followed by the target. after elimination, each
+/// pivot row's only non-zero button entries are its pivot and free buttons.
+struct Reduced {
+    rows: Vec<Vec<i128>>,
+    /// the pivot column of each of the first pivots.len() rows
+    pivots: Vec<usize>,
+    /// the buttons whose presses aren't decided by the others
+    free: Vec<usize>,
+}
+
+/// reduces the problem's equations with fraction-free gauss-jordan
+/// elimination, dividing each row through by its gcd to keep values small.
+/// returns None if the target isn't reachable even with fractional presses.
+fn reduce(problem: &GeneralProblem) -> Result<Option<Reduced>, Overflow> {
+    let n_buttons: usize = problem.buttons.len();
+    let mut rows: Vec<Vec<i128>> = problem
+        .target
+        .iter()
+        .enumerate()
+        .map(|(dim, target)| {
+            problem
+                .buttons
+                .iter()
+                .map(|button| button.offsets[dim])
+                .chain(std::iter::once(*target))
+                .collect()
+        })
+        .collect();
+
+    let mut pivots: Vec<usize> = Vec::new();
+    let mut free: Vec<usize> = Vec::new();
+    for col in 0..n_buttons {
+        let pivot_row: usize = pivots.len();
+        let Some(found) = (pivot_row..rows.len()).find(|row| rows[*row][col] != 0) else {
+            free.push(col);
+            continue;
+        };
+        rows.swap(pivot_row, found);
+
+        // clear this column out of every other row
+        for row_idx in (0..rows.len()).filter(|row_idx| *row_idx != pivot_row) {
+            let (factor, pivot) = (rows[row_idx][col], rows[pivot_row][col]);
+            if factor == 0 {
+                continue;
+            }
+            let new_row: Vec<i128> = rows[row_idx]
+                .iter()
+                .zip(rows[pivot_row].iter())
+                .map(|(val, pivot_val)| cross(*val, pivot, *pivot_val, factor))
+                .collect::<Result<_, _>>()?;
+            let divisor: i128 = new_row.iter().fold(0, |acc, val| gcd(acc, *val)).max(1);
+            rows[row_idx] = new_row.iter().map(|val| val / divisor).collect();
+        }
+        pivots.push(col);
+    }
+
+    // rows without a pivot say 0 == target, which had better be true
+    if rows[pivots.len()..].iter().any(|row| row[n_buttons] != 0) {
+        return Ok(None);
+    }
+    Ok(Some(Reduced { rows, pivots, free }))
+}
+
+/// finds the cheapest (cost, presses per button) that exactly reaches the
+/// target. the buttons whose presses are decided by the others are solved for
+/// directly, so only the rest are searched, which keeps problems with as many
+/// independent buttons as dimensions cheap however far away the target is.
+pub fn solve_general_problem(
+    problem: &GeneralProblem,
+) -> Result<Option<(i128, Vec<i128>)>, SolveError> {
+    if let Some(button) = problem
+        .buttons
+        .iter()
+        .find(|button| button.offsets.len() != problem.target.len())
+    {
+        return Err(SolveError::WrongDimensions(button.label));
+    }
+    let n_buttons: usize = problem.buttons.len();
+    let Some(Reduced { rows, pivots, free }) = reduce(problem)? else {
+        return Ok(None);
+    };
+
+    // every free button needs a limit, and together they can't need too many
+    // combinations
+    let free_bounds: Vec<i128> = free
+        .iter()
+        .map(|idx| {
+            problem
+                .press_bound(*idx)
+                .ok_or(SolveError::Unbounded(problem.buttons[*idx].label))
+        })
+        .collect::<Result<_, _>>()?;
+    if free_bounds.iter().any(|bound| *bound < 0) {
+        return Ok(None);
+    }
+    let search_size: u128 = free_bounds
+        .iter()
+        .map(|bound| *bound as u128 + 1)
+        .try_fold(1u128, |acc, bound| acc.checked_mul(bound))
+        .unwrap_or(u128::MAX);
+    if search_size > MAX_SEARCH_SIZE {
+        return Err(SolveError::SearchTooLarge(search_size));
+    }
+
+    // try every combination of free presses, working out the rest from them
+    let mut best: Option<(i128, Vec<i128>)> = None;
+    let mut free_presses: Vec<i128> = vec![0; free.len()];
+    'search: loop {
+        let mut presses: Vec<i128> = vec![0; n_buttons];
+        for (idx, n_presses) in free.iter().zip(free_presses.iter()) {
+            presses[*idx] = *n_presses;
+        }
+
+        let mut valid: bool = true;
+        for (row, pivot) in rows.iter().zip(pivots.iter()) {
+            let mut rest: i128 = row[n_buttons];
+            for (idx, n_presses) in free.iter().zip(free_presses.iter()) {
+                rest = cross(rest, 1, row[*idx], *n_presses)?;
+            }
+            let n_presses: i128 = rest / row[*pivot];
+            let button: &Button = &problem.buttons[*pivot];
+            if rest % row[*pivot] != 0
+                || n_presses < 0
+                || button.max_presses.is_some_and(|max| n_presses > max)
+            {
+                valid = false;
+                break;
+            }
+            presses[*pivot] = n_presses;
+        }
+
+        if valid {
+            let cost: i128 = presses
+                .iter()
+                .zip(problem.buttons.iter())
+                .try_fold(0i128, |acc, (n_presses, button)| {
+                    acc.checked_add(n_presses.checked_mul(button.cost)?)
+                })
+                .ok_or(Overflow("token cost"))?;
+            if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                best = Some((cost, presses));
+            }
+        }
+
+        // move on to the next combination of free presses
+        for (n_presses, bound) in free_presses.iter_mut().zip(free_bounds.iter()) {
+            if *n_presses < *bound {
+                *n_presses += 1;
+                continue 'search;
+            }
+            *n_presses = 0;
+        }
+        break;
+    }
+
+    Ok(best)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::parse_general_problems;
+
+    // parses a single machine and returns the cheapest (cost, presses)
+    fn solve(machine: &str) -> Option<(i128, Vec<i128>)> {
+        let problems: Vec<GeneralProblem> = parse_general_problems(machine).unwrap();
+        solve_general_problem(&problems[0]).unwrap()
+    }
+
+    #[test]
+    fn solves_more_buttons_and_dimensions() {
+        let three_by_three = "\
+Button A: X+3, Y+0, Z+1
+Button B: X+0, Y+2, Z+1
+Button C: X+1, Y+1, Z+0, cost 2
+Prize: X=7, Y=8, Z=3";
+        assert_eq!(solve(three_by_three), Some((13, vec![1, 2, 4])));
+
+        // C is cheaper than A and B together, so it should be used wherever
+        // it can be
+        let spare_buttons = "\
+Button A: X+1, Y+0, cost 3
+Button B: X+0, Y+1, cost 3
+Button C: X+1, Y+1, cost 1
+Prize: X=5, Y=3";
+        assert_eq!(solve(spare_buttons), Some((9, vec![2, 0, 3])));
+    }
+
+    #[test]
+    fn handles_negative_offsets_and_far_targets() {
+        let backwards = "\
+Button A: X-2, Y+5
+Button B: X+4, Y-1
+Prize: X=8, Y=7";
+        assert_eq!(solve(backwards), Some((9, vec![2, 3])));
+
+        let far = GeneralProblem::from_problem(
+            &Problem {
+                target: [12748, 12176],
+                a_values: [26, 66],
+                b_values: [67, 21],
+            },
+            &SolveConfig::part_2(),
+        )
+        .unwrap();
+        assert_eq!(
+            solve_general_problem(&far).unwrap().map(|(cost, _)| cost),
+            Some(459236326669)
+        );
+    }
+
+    #[test]
+    fn reports_unreachable_and_unbounded_machines() {
+        assert_eq!(solve("Button A: X+2\nPrize: X=5"), None);
+        assert_eq!(solve("Button A: X+1, Y+1\nPrize: X=2, Y=3"), None);
+
+        let problems = parse_general_problems("Button A: X+1\nButton B: X-1\nPrize: X=1").unwrap();
+        assert_eq!(
+            solve_general_problem(&problems[0]),
+            Err(SolveError::Unbounded('B'))
+        );
+    }
+}
@@ -4,225 +4,285 @@
  * desc:    Advent of Code 2024 day 13.
  */
 
-use z3::*;
+mod exact;
+mod general;
+mod parse;
+#[cfg(feature = "z3")]
+mod z3_solver;
+
+use aoc_common::input::{ArgError, Args, DayInputs, InputName, InputSource};
+use general::GeneralProblem;
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
-struct Problem {
-    target: [usize; 2],
-    a_values: [usize; 2],
-    b_values: [usize; 2],
+pub struct Problem {
+    pub target: [i128; 2],
+    pub a_values: [i128; 2],
+    pub b_values: [i128; 2],
 }
 
+/// describes the variant of the puzzle being solved
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum ProblemParseState {
-    Empty,
-    ParsedA,
-    ParsedB,
+pub struct SolveConfig {
+    /// the most times each of (A, B) may be pressed, if limited
+    pub max_presses: [Option<i128>; 2],
+    /// the number of tokens each press of (A, B) costs
+    pub token_costs: [i128; 2],
+    /// added to both of the prize's coordinates before solving
+    pub target_offset: i128,
 }
 
-/// parses the input into structs
-fn parse_problems(input: &str) -> Result<Vec<Problem>, ()> {
-    let mut problems: Vec<Problem> = Vec::new();
-    let mut parse_state: ProblemParseState = ProblemParseState::Empty;
-    let mut last_a: [usize; 2] = [0; 2];
-    let mut last_b: [usize; 2] = [0; 2];
-    for line in input.lines() {
-        match parse_state {
-            ProblemParseState::Empty => {
-                // skip empty lines between entries
-                if line == "" {
-                    continue;
-                }
-
-                // parse the A values
-                assert!(line.contains("Button A:"));
-                let x_plus_ofs: usize = line.find("X+").ok_or(())?;
-                let comma_ofs: usize = line[x_plus_ofs..line.len()].find(",").ok_or(())?;
-                let x_add: usize = line[(x_plus_ofs + 2)..(x_plus_ofs + comma_ofs)]
-                    .parse::<usize>()
-                    .or(Err(()))?;
-                let y_plus_ofs: usize = line.find("Y+").unwrap();
-                let y_add: usize = line[(y_plus_ofs + 2)..line.len()]
-                    .parse::<usize>()
-                    .or(Err(()))?;
-                last_a = [x_add, y_add];
-
-                // update state
-                parse_state = ProblemParseState::ParsedA;
-            }
-            ProblemParseState::ParsedA => {
-                // parse the B values
-                assert!(line.contains("Button B:"));
-                let x_plus_ofs: usize = line.find("X+").ok_or(())?;
-                let comma_ofs: usize = line[x_plus_ofs..line.len()].find(",").ok_or(())?;
-                let x_add: usize = line[(x_plus_ofs + 2)..(x_plus_ofs + comma_ofs)]
-                    .parse::<usize>()
-                    .or(Err(()))?;
-                let y_plus_ofs: usize = line.find("Y+").unwrap();
-                let y_add: usize = line[(y_plus_ofs + 2)..line.len()]
-                    .parse::<usize>()
-                    .or(Err(()))?;
-                last_b = [x_add, y_add];
-
-                // update state
-                parse_state = ProblemParseState::ParsedB;
-            }
-            ProblemParseState::ParsedB => {
-                // parse the Prize values
-                assert!(line.contains("Prize:"));
-                let x_eq_ofs: usize = line.find("X=").ok_or(())?;
-                let comma_ofs: usize = line[x_eq_ofs..line.len()].find(",").ok_or(())?;
-                let x_val: usize = line[(x_eq_ofs + 2)..(x_eq_ofs + comma_ofs)]
-                    .parse::<usize>()
-                    .or(Err(()))?;
-                let y_eq_ofs: usize = line.find("Y=").unwrap();
-                let y_val: usize = line[(y_eq_ofs + 2)..line.len()]
-                    .parse::<usize>()
-                    .or(Err(()))?;
-
-                // add the new problem to the vec
-                problems.push(Problem {
-                    target: [x_val, y_val],
-                    a_values: last_a,
-                    b_values: last_b,
-                });
+impl SolveConfig {
+    /// the config from part 1: at most 100 presses of each button
+    fn part_1() -> Self {
+        Self {
+            max_presses: [Some(100); 2],
+            token_costs: [3, 1],
+            target_offset: 0,
+        }
+    }
 
-                // update state
-                parse_state = ProblemParseState::Empty;
-            }
+    /// the config from part 2: unlimited presses, but a much further prize
+    fn part_2() -> Self {
+        Self {
+            max_presses: [None; 2],
+            target_offset: 10000000000000,
+            ..Self::part_1()
         }
     }
+}
+
+/// solves every machine with the general solver, so the input may have any
+/// number of buttons and dimensions. if it's in the usual two-button form,
+/// every machine is cross-checked against the exact solver too.
+fn solve_general(input: &str, inputs: &DayInputs, source: &InputSource) {
+    let problems: Vec<GeneralProblem> = match parse::parse_general_problems(input) {
+        Ok(problems) => problems,
+        Err(e) => {
+            eprintln!("failed to parse {} - {}", source, e);
+            std::process::exit(1);
+        }
+    };
+    let two_button_problems: Option<Vec<Problem>> = parse::parse_problems(input).ok();
+    println!("# of problems: {}", problems.len());
 
-    // double check that we haven't had a partial parse
-    assert_eq!(parse_state, ProblemParseState::Empty);
+    for (part_idx, config) in [SolveConfig::part_1(), SolveConfig::part_2()]
+        .iter()
+        .enumerate()
+    {
+        let mut total_n_tokens: Option<i128> = Some(0);
+        let mut n_skipped: usize = 0;
+        for (problem_idx, problem) in problems.iter().enumerate() {
+            // both parts limit every button the same way
+            let configured: GeneralProblem =
+                match problem.with_limits(config.max_presses[0], config.target_offset) {
+                    Ok(configured) => configured,
+                    Err(e) => {
+                        println!(
+                            "Part {} - machine {}: skipped - {}",
+                            part_idx + 1,
+                            problem_idx + 1,
+                            e
+                        );
+                        n_skipped += 1;
+                        continue;
+                    }
+                };
+            let n_tokens: Option<i128> = match general::solve_general_problem(&configured) {
+                Ok(solution) => solution.map(|(n_tokens, _)| n_tokens),
+                Err(e) => {
+                    println!(
+                        "Part {} - machine {}: skipped - {}",
+                        part_idx + 1,
+                        problem_idx + 1,
+                        e
+                    );
+                    n_skipped += 1;
+                    continue;
+                }
+            };
+            total_n_tokens =
+                total_n_tokens.and_then(|total| total.checked_add(n_tokens.unwrap_or(0)));
 
-    // yield the parsed problems
-    Ok(problems)
-}
+            if let Some(two_button_problem) = two_button_problems
+                .as_ref()
+                .map(|problems| &problems[problem_idx])
+            {
+                assert_eq!(
+                    GeneralProblem::from_problem(two_button_problem, config)
+                        .ok()
+                        .as_ref(),
+                    Some(&configured),
+                    "general form differs for {:?}",
+                    two_button_problem
+                );
+                assert_eq!(
+                    n_tokens,
+                    exact::solve_single_problem(two_button_problem, config)
+                        .ok()
+                        .flatten(),
+                    "general solver disagrees on {:?}",
+                    two_button_problem
+                );
+            }
 
-/// uses z3 to try and solve a single problem
-fn solve_single_problem(problem: &Problem) -> Option<usize> {
-    let cfg = Config::new();
-    let ctx = Context::new(&cfg);
-    let optimize = Optimize::new(&ctx);
-
-    // define our a and b variables, constrained by [0, 100]
-    let a = ast::Int::new_const(&ctx, "a");
-    let b = ast::Int::new_const(&ctx, "b");
-    let a_le_100 = a.le(&ast::Int::from_u64(&ctx, 100));
-    let b_le_100 = b.le(&ast::Int::from_u64(&ctx, 100));
-
-    // define constants based on the problem struct
-    let a_xstep = ast::Int::from_u64(&ctx, problem.a_values[0] as u64);
-    let a_ystep = ast::Int::from_u64(&ctx, problem.a_values[1] as u64);
-    let b_xstep = ast::Int::from_u64(&ctx, problem.b_values[0] as u64);
-    let b_ystep = ast::Int::from_u64(&ctx, problem.b_values[1] as u64);
-    let target_x = ast::Int::from_u64(&ctx, problem.target[0] as u64);
-    let target_y = ast::Int::from_u64(&ctx, problem.target[1] as u64);
-
-    // generate variables for x and y based on a and b
-    let x = (a_xstep * &a) + (b_xstep * &b);
-    let y = (a_ystep * &a) + (b_ystep * &b);
-
-    let x_le = x.le(&target_x);
-    let x_not_lt = x.lt(&target_x).not();
-    let x_eq = ast::Bool::and(&ctx, &[&x_le, &x_not_lt]);
-    let y_le = y.le(&target_y);
-    let y_not_lt = y.lt(&target_y).not();
-    let y_eq = ast::Bool::and(&ctx, &[&y_le, &y_not_lt]);
-
-    // calculate the number of tokens
-    let n_tokens = (&a * ast::Int::from_u64(&ctx, 3)) + &b;
-
-    optimize.assert(&a_le_100);
-    optimize.assert(&b_le_100);
-    optimize.assert(&x_eq);
-    optimize.assert(&y_eq);
-    optimize.minimize(&n_tokens);
-
-    // return our result
-    if optimize.check(&[]) == SatResult::Sat {
-        let model = optimize.get_model().unwrap();
-        Some(model.eval(&n_tokens, true).unwrap().as_u64().unwrap() as usize)
-    } else {
-        None
-    }
-}
+            #[cfg(feature = "z3")]
+            assert_eq!(
+                z3_solver::solve_general_problem(&configured),
+                n_tokens,
+                "general z3 solver disagrees on machine {}",
+                problem_idx + 1
+            );
+        }
 
-/// uses z3 to try and solve a single problem
-fn solve_single_problem_unconstrained(problem: &Problem) -> Option<usize> {
-    let cfg = Config::new();
-    let ctx = Context::new(&cfg);
-    let optimize = Optimize::new(&ctx);
-
-    // define our a and b variables
-    let a = ast::Int::new_const(&ctx, "a");
-    let b = ast::Int::new_const(&ctx, "b");
-
-    // define constants based on the problem struct
-    let a_xstep = ast::Int::from_u64(&ctx, problem.a_values[0] as u64);
-    let a_ystep = ast::Int::from_u64(&ctx, problem.a_values[1] as u64);
-    let b_xstep = ast::Int::from_u64(&ctx, problem.b_values[0] as u64);
-    let b_ystep = ast::Int::from_u64(&ctx, problem.b_values[1] as u64);
-    let target_x = ast::Int::from_u64(&ctx, problem.target[0] as u64);
-    let target_y = ast::Int::from_u64(&ctx, problem.target[1] as u64);
-
-    // generate variables for x and y based on a and b
-    let x = (a_xstep * &a) + (b_xstep * &b);
-    let y = (a_ystep * &a) + (b_ystep * &b);
-
-    let x_le = x.le(&target_x);
-    let x_not_lt = x.lt(&target_x).not();
-    let x_eq = ast::Bool::and(&ctx, &[&x_le, &x_not_lt]);
-    let y_le = y.le(&target_y);
-    let y_not_lt = y.lt(&target_y).not();
-    let y_eq = ast::Bool::and(&ctx, &[&y_le, &y_not_lt]);
-
-    // calculate the number of tokens
-    let n_tokens = (&a * ast::Int::from_u64(&ctx, 3)) + &b;
-
-    optimize.assert(&x_eq);
-    optimize.assert(&y_eq);
-    optimize.minimize(&n_tokens);
-
-    // return our result
-    if optimize.check(&[]) == SatResult::Sat {
-        let model = optimize.get_model().unwrap();
-        Some(model.eval(&n_tokens, true).unwrap().as_u64().unwrap() as usize)
-    } else {
-        None
+        match total_n_tokens {
+            Some(total_n_tokens) => {
+                println!("Part {}: {}", part_idx + 1, total_n_tokens);
+                inputs.report_answer(source, part_idx as u32 + 1, &total_n_tokens);
+            }
+            None => println!(
+                "Part {}: failed - total token cost doesn't fit in an i128",
+                part_idx + 1
+            ),
+        }
+        println!(
+            "Part {}: general solver {} {} problems, skipped {}",
+            part_idx + 1,
+            if two_button_problems.is_some() {
+                "agreed with the exact solver on"
+            } else {
+                "solved"
+            },
+            problems.len() - n_skipped,
+            n_skipped
+        );
     }
 }
 
 /// the entrypoint
 fn main() {
+    // parse the optional arguments, e.g. `day_13 --input example --report` or
+    // `day_13 --general --input -`
+    let mut general: bool = false;
+    let mut report: bool = false;
+    let mut args = Args::from_env();
+    for arg in args.by_ref() {
+        match arg.as_str() {
+            "--general" => general = true,
+            "--report" => report = true,
+            _ => ArgError::Unknown(arg).exit(),
+        }
+    }
+
     // parse the input into structs
-    let input: String = std::fs::read_to_string("input.txt").unwrap();
-    let problems: Vec<Problem> = parse_problems(&input).unwrap();
+    let source: InputSource = args.source().clone();
+    let inputs = DayInputs::new(13, env!("CARGO_MANIFEST_DIR"))
+        .embed(InputName::Example(1), include_str!("../test-input.txt"));
+    let input: String = inputs.load_or_exit(&source);
+
+    // machines with any number of buttons need the general solver
+    if general {
+        solve_general(&input, &inputs, &source);
+        return;
+    }
+    let problems: Vec<Problem> = match parse::parse_problems(&input) {
+        Ok(problems) => problems,
+        Err(e) => {
+            eprintln!("failed to parse {} - {}", source, e);
+            std::process::exit(1);
+        }
+    };
 
     // metadata
     println!("# of problems: {}", problems.len());
 
-    // part 1
-    let total_n_tokens: usize = problems.iter().map(solve_single_problem).flatten().sum();
-    println!("Part 1: {}", total_n_tokens);
-
-    // part 2
-    let adjusted_problems: Vec<Problem> = problems
-        .iter()
-        .map(|problem| Problem {
-            target: [
-                problem.target[0] + 10000000000000,
-                problem.target[1] + 10000000000000,
-            ],
-            a_values: problem.a_values,
-            b_values: problem.b_values,
-        })
-        .collect();
-    let total_n_tokens: usize = adjusted_problems
+    // solve each part, cross-checking the exact solver against z3 if it's
+    // available
+    for (part_idx, config) in [SolveConfig::part_1(), SolveConfig::part_2()]
         .iter()
-        .map(solve_single_problem_unconstrained)
-        .flatten()
-        .sum();
-    println!("Part 2: {}", total_n_tokens);
+        .enumerate()
+    {
+        let total_n_tokens: Result<i128, String> =
+            problems
+                .iter()
+                .enumerate()
+                .try_fold(0i128, |total, (problem_idx, problem)| {
+                    let n_tokens = exact::solve_single_problem(problem, config)
+                        .map_err(|e| format!("problem {}: {}", problem_idx + 1, e))?;
+                    total
+                        .checked_add(n_tokens.unwrap_or(0))
+                        .ok_or_else(|| String::from("total token cost doesn't fit in an i128"))
+                });
+        match total_n_tokens {
+            Ok(total_n_tokens) => {
+                println!("Part {}: {}", part_idx + 1, total_n_tokens);
+                inputs.report_answer(&source, part_idx as u32 + 1, &total_n_tokens);
+            }
+            Err(e) => println!("Part {}: failed - {}", part_idx + 1, e),
+        }
+
+        // optionally explain how each machine was (or wasn't) solved
+        if report {
+            for (problem_idx, problem) in problems.iter().enumerate() {
+                let explanation: String = match exact::solve_outcome(problem, config) {
+                    Ok(exact::Outcome::Solved { a, b, tokens }) => {
+                        format!("A x{} + B x{} = {} tokens", a, b, tokens)
+                    }
+                    Ok(exact::Outcome::Unsolvable(reason)) => format!("unsolvable - {}", reason),
+                    Err(e) => format!("failed - {}", e),
+                };
+                println!(
+                    "Part {} - machine {} {:?}: {}",
+                    part_idx + 1,
+                    problem_idx + 1,
+                    problem,
+                    explanation
+                );
+            }
+        }
+
+        #[cfg(feature = "z3")]
+        for problem in problems.iter() {
+            assert_eq!(
+                exact::solve_single_problem(problem, config).ok().flatten(),
+                z3_solver::solve_single_problem(problem, config),
+                "solvers disagree on {:?}",
+                problem
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_input() {
+        let input: &str = include_str!("../test-input.txt");
+        let problems: Vec<Problem> = parse::parse_problems(input).unwrap();
+        let general_problems: Vec<GeneralProblem> = parse::parse_general_problems(input).unwrap();
+        for (config, expected) in [
+            (SolveConfig::part_1(), 480),
+            (SolveConfig::part_2(), 875318608908),
+        ] {
+            let exact_total: i128 = problems
+                .iter()
+                .map(|problem| exact::solve_single_problem(problem, &config).unwrap())
+                .map(|n_tokens| n_tokens.unwrap_or(0))
+                .sum();
+            assert_eq!(exact_total, expected);
+
+            let general_total: i128 = general_problems
+                .iter()
+                .map(|problem| {
+                    let configured = problem
+                        .with_limits(config.max_presses[0], config.target_offset)
+                        .unwrap();
+                    general::solve_general_problem(&configured).unwrap()
+                })
+                .map(|solution| solution.map_or(0, |(n_tokens, _)| n_tokens))
+                .sum();
+            assert_eq!(general_total, expected);
+        }
+    }
 }
@@ -0,0 +1,384 @@
+/* file:    parse.rs
+ * author:  garnt
+ * date:    01/02/2025
+ * desc:    Parser for day 13's "Button A/Button B/Prize" blocks.
+ */
+
+use crate::general::{Button, GeneralProblem};
+use crate::Problem;
+use std::fmt;
+
+/// an error encountered while parsing the input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// the 1-based line number the error was found on
+    pub line_no: usize,
+    pub msg: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line_no, self.msg)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// a general button's label, each (axis, offset), and its cost if it has one
+type ButtonLine = (char, Vec<(char, i128)>, Option<i128>);
+
+/// walks through a single line, consuming one token at a time
+struct LineParser<'a> {
+    line_no: usize,
+    rest: &'a str,
+}
+
+impl<'a> LineParser<'a> {
+    fn new(line_no: usize, line: &'a str) -> Self {
+        Self {
+            line_no,
+            rest: line,
+        }
+    }
+
+    // builds an error pointing at the current line
+    fn error(&self, msg: String) -> ParseError {
+        ParseError {
+            line_no: self.line_no,
+            msg,
+        }
+    }
+
+    // describes what's left of the line, for error messages
+    fn found(&self) -> String {
+        if self.rest.is_empty() {
+            String::from("end of line")
+        } else {
+            format!("'{}'", self.rest)
+        }
+    }
+
+    /// consumes the provided literal, ignoring any leading whitespace
+    fn expect(&mut self, literal: &str) -> Result<(), ParseError> {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(literal) {
+            Some(rest) => {
+                self.rest = rest;
+                Ok(())
+            }
+            None => Err(self.error(format!("expected '{}', found {}", literal, self.found()))),
+        }
+    }
+
+    /// consumes an integer with an optional sign, ignoring any leading
+    /// whitespace
+    fn int(&mut self) -> Result<i128, ParseError> {
+        self.rest = self.rest.trim_start();
+        let sign_len: usize = usize::from(self.rest.starts_with(['+', '-']));
+        let digits_len: usize = self.rest[sign_len..]
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len() - sign_len);
+        if digits_len == 0 {
+            return Err(self.error(format!("expected a number, found {}", self.found())));
+        }
+
+        let (num_str, rest) = self.rest.split_at(sign_len + digits_len);
+        let num: i128 = num_str
+            .parse::<i128>()
+            .map_err(|e| self.error(format!("invalid number '{}': {}", num_str, e)))?;
+        self.rest = rest;
+        Ok(num)
+    }
+
+    /// makes sure nothing but whitespace is left on the line
+    fn end(&mut self) -> Result<(), ParseError> {
+        self.rest = self.rest.trim_start();
+        if self.rest.is_empty() {
+            Ok(())
+        } else {
+            Err(self.error(format!("unexpected trailing {}", self.found())))
+        }
+    }
+
+    /// parses "Button <label>: X+<int>, Y+<int>", where either sign may be
+    /// negative
+    fn button(&mut self, label: char) -> Result<[i128; 2], ParseError> {
+        self.expect(&format!("Button {}:", label))?;
+        self.expect("X")?;
+        let x: i128 = self.int()?;
+        self.expect(",")?;
+        self.expect("Y")?;
+        let y: i128 = self.int()?;
+        self.end()?;
+        Ok([x, y])
+    }
+
+    /// consumes a single axis letter, like the X in "X+94"
+    fn axis(&mut self) -> Result<char, ParseError> {
+        self.rest = self.rest.trim_start();
+        match self.rest.chars().next() {
+            Some(axis) if axis.is_ascii_uppercase() => {
+                self.rest = &self.rest[1..];
+                Ok(axis)
+            }
+            _ => Err(self.error(format!("expected an axis letter, found {}", self.found()))),
+        }
+    }
+
+    /// parses "Button <label>: <axis><int>, <axis><int>, ...", with an optional
+    /// trailing ", cost <int>", returning the label, each (axis, offset) and
+    /// the cost if there was one
+    fn general_button(&mut self) -> Result<ButtonLine, ParseError> {
+        self.expect("Button")?;
+        self.rest = self.rest.trim_start();
+        let label: char = match self.rest.chars().next() {
+            Some(label) if label != ':' && !label.is_whitespace() => label,
+            _ => return Err(self.error(format!("expected a button label, found {}", self.found()))),
+        };
+        self.rest = &self.rest[label.len_utf8()..];
+        self.expect(":")?;
+
+        let mut offsets: Vec<(char, i128)> = Vec::new();
+        loop {
+            if self.rest.trim_start().starts_with("cost") {
+                self.expect("cost")?;
+                let cost: i128 = self.int()?;
+                self.end()?;
+                return Ok((label, offsets, Some(cost)));
+            }
+            let axis: char = self.axis()?;
+            offsets.push((axis, self.int()?));
+            if self.rest.trim().is_empty() {
+                return Ok((label, offsets, None));
+            }
+            self.expect(",")?;
+        }
+    }
+
+    /// parses "Prize: <axis>=<int>, <axis>=<int>, ..."
+    fn general_prize(&mut self) -> Result<Vec<(char, i128)>, ParseError> {
+        self.expect("Prize:")?;
+        let mut target: Vec<(char, i128)> = Vec::new();
+        loop {
+            let axis: char = self.axis()?;
+            self.expect("=")?;
+            target.push((axis, self.int()?));
+            if self.rest.trim().is_empty() {
+                return Ok(target);
+            }
+            self.expect(",")?;
+        }
+    }
+
+    /// parses "Prize: X=<int>, Y=<int>"
+    fn prize(&mut self) -> Result<[i128; 2], ParseError> {
+        self.expect("Prize:")?;
+        self.expect("X=")?;
+        let x: i128 = self.int()?;
+        self.expect(",")?;
+        self.expect("Y=")?;
+        let y: i128 = self.int()?;
+        self.end()?;
+        Ok([x, y])
+    }
+}
+
+/// parses the input into structs. blocks may be separated by any number of
+/// blank lines, and lines may end in either "\n" or "\r\n".
+pub fn parse_problems(input: &str) -> Result<Vec<Problem>, ParseError> {
+    let mut problems: Vec<Problem> = Vec::new();
+    let mut lines = input
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .peekable();
+    let n_lines: usize = input.lines().count();
+
+    while lines.peek().is_some() {
+        // grab the next line of the block, complaining if the input ends early
+        let mut next_line = |what: &str| {
+            lines
+                .next()
+                .map(|(line_no, line)| LineParser::new(line_no, line))
+                .ok_or_else(|| ParseError {
+                    line_no: n_lines + 1,
+                    msg: format!("expected {}, found end of input", what),
+                })
+        };
+
+        let a_values = next_line("'Button A' line")?.button('A')?;
+        let b_values = next_line("'Button B' line")?.button('B')?;
+        let target = next_line("'Prize' line")?.prize()?;
+        problems.push(Problem {
+            target,
+            a_values,
+            b_values,
+        });
+    }
+
+    // yield the parsed problems
+    Ok(problems)
+}
+
+// the puzzle's prices, for buttons that don't list their own: A costs 3
+// tokens, and every other button costs 1
+fn default_cost(label: char) -> i128 {
+    if label == 'A' {
+        3
+    } else {
+        1
+    }
+}
+
+/// parses machines with any number of buttons, in any number of dimensions.
+/// each block is one or more "Button <label>: <axis><int>, ..." lines, which
+/// may end in ", cost <int>", followed by a "Prize: <axis>=<int>, ..." line.
+/// every button has to move along the prize's axes, in the same order.
+pub fn parse_general_problems(input: &str) -> Result<Vec<GeneralProblem>, ParseError> {
+    let mut problems: Vec<GeneralProblem> = Vec::new();
+    let mut lines = input
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .peekable();
+    let n_lines: usize = input.lines().count();
+
+    while lines.peek().is_some() {
+        // gather every button line, remembering where each one was
+        let mut buttons: Vec<(usize, ButtonLine)> = Vec::new();
+        while let Some((line_no, line)) =
+            lines.next_if(|(_, line)| line.trim_start().starts_with("Button"))
+        {
+            buttons.push((line_no, LineParser::new(line_no, line).general_button()?));
+        }
+        let (prize_line_no, prize_line) = lines.next().ok_or_else(|| ParseError {
+            line_no: n_lines + 1,
+            msg: String::from("expected 'Prize' line, found end of input"),
+        })?;
+        if buttons.is_empty() {
+            return Err(ParseError {
+                line_no: prize_line_no,
+                msg: format!("expected 'Button' line, found '{}'", prize_line),
+            });
+        }
+        let target: Vec<(char, i128)> =
+            LineParser::new(prize_line_no, prize_line).general_prize()?;
+
+        // make sure everything agrees on which axes there are
+        let axes: Vec<char> = target.iter().map(|(axis, _)| *axis).collect();
+        let axes_str = |axes: &[char]| {
+            axes.iter()
+                .map(char::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut problem = GeneralProblem {
+            target: target.iter().map(|(_, val)| *val).collect(),
+            buttons: Vec::new(),
+        };
+        for (line_no, (label, offsets, cost)) in buttons {
+            let button_axes: Vec<char> = offsets.iter().map(|(axis, _)| *axis).collect();
+            if button_axes != axes {
+                return Err(ParseError {
+                    line_no,
+                    msg: format!(
+                        "button {} moves along {} but the prize is at {}",
+                        label,
+                        axes_str(&button_axes),
+                        axes_str(&axes)
+                    ),
+                });
+            }
+            problem.buttons.push(Button {
+                label,
+                offsets: offsets.iter().map(|(_, val)| *val).collect(),
+                cost: cost.unwrap_or_else(|| default_cost(label)),
+                max_presses: None,
+            });
+        }
+        problems.push(problem);
+    }
+
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the error message parse_problems gives for the input
+    fn error_msg(input: &str) -> String {
+        parse_problems(input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn crlf_blank_lines_and_negative_offsets() {
+        let input: &str = concat!(
+            "\r\n",
+            "Button A: X+94, Y+34\r\n",
+            "Button B: X-22, Y+67\r\n",
+            "Prize: X=8400, Y=-5400\r\n",
+            "\r\n\r\n",
+            "Button A: X-1, Y-2\n",
+            "Button B: X+3, Y-0\n",
+            "Prize: X=-5, Y=+6\n",
+            "\n\r\n\n",
+        );
+        assert_eq!(
+            parse_problems(input),
+            Ok(vec![
+                Problem {
+                    target: [8400, -5400],
+                    a_values: [94, 34],
+                    b_values: [-22, 67],
+                },
+                Problem {
+                    target: [-5, 6],
+                    a_values: [-1, -2],
+                    b_values: [3, 0],
+                },
+            ])
+        );
+        assert_eq!(parse_problems("\n\r\n"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn errors_name_the_line() {
+        let block_a: &str = "Button A: X+94, Y+34\n";
+        let prize: &str = "Prize: X=8400, Y=5400\n";
+        assert_eq!(
+            error_msg(&format!("{}Button B: X+22 Y+67\n{}", block_a, prize)),
+            "line 2: expected ',', found 'Y+67'"
+        );
+        assert_eq!(
+            error_msg(&format!("{}Button B: X+, Y+67\n{}", block_a, prize)),
+            "line 2: expected a number, found '+, Y+67'"
+        );
+        assert_eq!(
+            error_msg(&format!("{}Button C: X+22, Y+67\n{}", block_a, prize)),
+            "line 2: expected 'Button B:', found 'Button C: X+22, Y+67'"
+        );
+        assert_eq!(
+            error_msg(&format!("{}Button B: X+22, Y+67 Z+1\n{}", block_a, prize)),
+            "line 2: unexpected trailing 'Z+1'"
+        );
+
+        // a block that's cut short is reported just past the end of the input
+        let two_blocks: String = format!(
+            "{}Button B: X+22, Y+67\n{}\n{}Button B: X+22, Y+67\n",
+            block_a, prize, block_a
+        );
+        assert_eq!(
+            error_msg(&two_blocks),
+            "line 7: expected 'Prize' line, found end of input"
+        );
+        assert_eq!(
+            error_msg(block_a),
+            "line 2: expected 'Button B' line, found end of input"
+        );
+    }
+}
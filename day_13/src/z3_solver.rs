@@ -0,0 +1,126 @@
+/* file:    z3_solver.rs
+ * author:  garnt
+ * date:    01/02/2025
+ * desc:    z3-backed solver for day 13 claw machines, used for cross-checking.
+ */
+
+use crate::general::GeneralProblem;
+use crate::{Problem, SolveConfig};
+use z3::ast::Ast;
+use z3::*;
+
+// builds a z3 integer constant from an i128, which z3 can't take directly
+fn int_const(ctx: &Context, val: i128) -> ast::Int<'_> {
+    ast::Int::from_str(ctx, &val.to_string()).unwrap()
+}
+
+// reads the value of an integer expression out of a model, if it fits
+fn eval_i128(model: &Model, expr: &ast::Int) -> Option<i128> {
+    model.eval(expr, true)?.to_string().parse::<i128>().ok()
+}
+
+/// uses z3 to try and solve a single problem, offset and limited as described
+/// by the config
+pub fn solve_single_problem(problem: &Problem, config: &SolveConfig) -> Option<i128> {
+    let cfg = Config::new();
+    let ctx = Context::new(&cfg);
+    let optimize = Optimize::new(&ctx);
+
+    // define our a and b variables, which can't be pressed a negative number of
+    // times, and might be capped
+    let a = ast::Int::new_const(&ctx, "a");
+    let b = ast::Int::new_const(&ctx, "b");
+    let zero = ast::Int::from_u64(&ctx, 0);
+    optimize.assert(&a.ge(&zero));
+    optimize.assert(&b.ge(&zero));
+    if let Some(a_max) = config.max_presses[0] {
+        optimize.assert(&a.le(&int_const(&ctx, a_max)));
+    }
+    if let Some(b_max) = config.max_presses[1] {
+        optimize.assert(&b.le(&int_const(&ctx, b_max)));
+    }
+
+    // define constants based on the problem struct
+    let a_xstep = int_const(&ctx, problem.a_values[0]);
+    let a_ystep = int_const(&ctx, problem.a_values[1]);
+    let b_xstep = int_const(&ctx, problem.b_values[0]);
+    let b_ystep = int_const(&ctx, problem.b_values[1]);
+    let target_x = int_const(&ctx, problem.target[0].checked_add(config.target_offset)?);
+    let target_y = int_const(&ctx, problem.target[1].checked_add(config.target_offset)?);
+
+    // generate variables for x and y based on a and b
+    let x = (a_xstep * &a) + (b_xstep * &b);
+    let y = (a_ystep * &a) + (b_ystep * &b);
+
+    let x_le = x.le(&target_x);
+    let x_not_lt = x.lt(&target_x).not();
+    let x_eq = ast::Bool::and(&ctx, &[&x_le, &x_not_lt]);
+    let y_le = y.le(&target_y);
+    let y_not_lt = y.lt(&target_y).not();
+    let y_eq = ast::Bool::and(&ctx, &[&y_le, &y_not_lt]);
+
+    // calculate the number of tokens
+    let a_cost = int_const(&ctx, config.token_costs[0]);
+    let b_cost = int_const(&ctx, config.token_costs[1]);
+    let n_tokens = (&a * a_cost) + (&b * b_cost);
+
+    optimize.assert(&x_eq);
+    optimize.assert(&y_eq);
+    optimize.minimize(&n_tokens);
+
+    // return our result
+    if optimize.check(&[]) == SatResult::Sat {
+        let model = optimize.get_model().unwrap();
+        eval_i128(&model, &n_tokens)
+    } else {
+        None
+    }
+}
+
+/// uses z3 to try and solve a generalised problem, returning the cheapest
+/// number of tokens
+pub fn solve_general_problem(problem: &GeneralProblem) -> Option<i128> {
+    let cfg = Config::new();
+    let ctx = Context::new(&cfg);
+    let optimize = Optimize::new(&ctx);
+
+    // define a variable for each button's number of presses
+    let zero = ast::Int::from_u64(&ctx, 0);
+    let presses: Vec<ast::Int> = (0..problem.buttons.len())
+        .map(|idx| ast::Int::new_const(&ctx, format!("presses_{}", idx)))
+        .collect();
+    for (n_presses, button) in presses.iter().zip(problem.buttons.iter()) {
+        optimize.assert(&n_presses.ge(&zero));
+        if let Some(max) = button.max_presses {
+            optimize.assert(&n_presses.le(&int_const(&ctx, max)));
+        }
+    }
+
+    // the claw has to land exactly on the target along every dimension
+    for (dim, target) in problem.target.iter().enumerate() {
+        let terms: Vec<ast::Int> = presses
+            .iter()
+            .zip(problem.buttons.iter())
+            .map(|(n_presses, button)| n_presses * int_const(&ctx, button.offsets[dim]))
+            .collect();
+        let pos = ast::Int::add(&ctx, &terms.iter().collect::<Vec<&ast::Int>>());
+        optimize.assert(&pos._eq(&int_const(&ctx, *target)));
+    }
+
+    // calculate the number of tokens
+    let costs: Vec<ast::Int> = presses
+        .iter()
+        .zip(problem.buttons.iter())
+        .map(|(n_presses, button)| n_presses * int_const(&ctx, button.cost))
+        .collect();
+    let n_tokens = ast::Int::add(&ctx, &costs.iter().collect::<Vec<&ast::Int>>());
+    optimize.minimize(&n_tokens);
+
+    // return our result
+    if optimize.check(&[]) == SatResult::Sat {
+        let model = optimize.get_model().unwrap();
+        eval_i128(&model, &n_tokens)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,256 @@
+/* file:    dampener.rs
+ * author:  garnt
+ * date:    12/21/2024
+ * desc:    The "Problem Dampener", which finds the fewest levels to remove
+ *          from a day 2 report to make it safe.
+ */
+
+use crate::report_rule::ReportRule;
+use std::cmp::Ordering;
+
+// for each kept level, the previous kept level, or None if every level before
+// it was removed
+type Parent = Option<usize>;
+
+/// returns the fewest indices to remove from the report so that what's left
+/// follows order under the rule, if that takes at most max_removals. runs in
+/// O(n * max_removals^2) time.
+pub fn removals_for_order(
+    report: &[isize],
+    rule: &ReportRule,
+    order: Ordering,
+    max_removals: usize,
+) -> Option<Vec<usize>> {
+    let n: usize = report.len();
+    if n <= 1 {
+        return Some(Vec::new());
+    }
+
+    // kept[i][j] is set if we can keep level i as the latest level, having
+    // removed exactly j of the levels before it, and holds the kept level
+    // before it so we can walk back through the solution
+    let mut kept: Vec<Vec<Option<Parent>>> = vec![vec![None; max_removals + 1]; n];
+    for i in 0..n {
+        for j in 0..=max_removals.min(i) {
+            if j == i {
+                kept[i][j] = Some(None);
+                continue;
+            }
+            // try every previous kept level p, removing the r levels between
+            kept[i][j] = (0..=j)
+                .filter(|r| *r < i)
+                .map(|r| (i - 1 - r, j - r))
+                .find(|(p, prev_j)| {
+                    kept[*p][*prev_j].is_some() && rule.pair_ok(report[*p], report[i], order)
+                })
+                .map(|(p, _)| Some(p));
+        }
+    }
+
+    // pick the cheapest last kept level, counting the levels removed after it
+    let (last, _, _) = (0..n)
+        .flat_map(|i| (0..=max_removals).map(move |j| (i, j, j + (n - 1 - i))))
+        .filter(|(i, j, total)| kept[*i][*j].is_some() && *total <= max_removals)
+        .min_by_key(|(_, _, total)| *total)?;
+
+    // walk back through the kept levels, starting from the cheapest ending
+    let mut is_kept: Vec<bool> = vec![false; n];
+    let mut j: usize = (0..=max_removals)
+        .filter(|j| kept[last][*j].is_some())
+        .min()
+        .unwrap();
+    let mut cursor: Option<usize> = Some(last);
+    while let Some(i) = cursor {
+        is_kept[i] = true;
+        let parent: Parent = kept[i][j].unwrap();
+        if let Some(p) = parent {
+            j -= i - 1 - p;
+        }
+        cursor = parent;
+    }
+
+    Some((0..n).filter(|i| !is_kept[*i]).collect())
+}
+
+/// returns the fewest indices to remove from the report to make it safe under
+/// the rule, if that takes at most max_removals
+pub fn find_removals(
+    report: &[isize],
+    rule: &ReportRule,
+    max_removals: usize,
+) -> Option<Vec<usize>> {
+    rule.orders()
+        .iter()
+        .filter_map(|order| removals_for_order(report, rule, *order, max_removals))
+        .min_by_key(|removed| removed.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report_rule::{Direction, FollowsOrder, StepRange};
+    use aoc_common::rng::XorShift;
+
+    // the rule from the puzzle: strictly increasing or decreasing, in steps of
+    // 1-3
+    fn puzzle_rule() -> ReportRule {
+        ReportRule::new(Direction::Either)
+            .with(FollowsOrder)
+            .with(StepRange::PUZZLE)
+    }
+
+    // returns the report without the levels at the provided (sorted) indices
+    fn without(report: &[isize], removed: &[usize]) -> Vec<isize> {
+        report
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| removed.binary_search(idx).is_err())
+            .map(|(_, level)| *level)
+            .collect()
+    }
+
+    // calls visit with every sorted combination of n_removed indices below len,
+    // stopping early and returning true as soon as visit does
+    fn any_combination(
+        len: usize,
+        n_removed: usize,
+        start: usize,
+        removed: &mut Vec<usize>,
+        visit: &mut impl FnMut(&[usize]) -> bool,
+    ) -> bool {
+        if removed.len() == n_removed {
+            return visit(removed);
+        }
+        for idx in start..len {
+            removed.push(idx);
+            let found: bool = any_combination(len, n_removed, idx + 1, removed, visit);
+            removed.pop();
+            if found {
+                return true;
+            }
+        }
+        false
+    }
+
+    // the slow but obviously correct version of find_removals, which tries every
+    // combination of removals from smallest to largest
+    fn brute_force_removals(
+        report: &[isize],
+        rule: &ReportRule,
+        max_removals: usize,
+    ) -> Option<Vec<usize>> {
+        let mut found: Option<Vec<usize>> = None;
+        for n_removed in 0..=max_removals.min(report.len()) {
+            let mut visit = |removed: &[usize]| {
+                let is_safe: bool = rule.is_safe(&without(report, removed));
+                if is_safe {
+                    found = Some(removed.to_vec());
+                }
+                is_safe
+            };
+            if any_combination(report.len(), n_removed, 0, &mut Vec::new(), &mut visit) {
+                break;
+            }
+        }
+        found
+    }
+
+    // compares find_removals against brute_force_removals for a single report,
+    // returning a description of the disagreement if there is one. the two may
+    // pick different indices, as long as they remove the same number and both
+    // leave a safe report.
+    fn check_against_oracle(
+        report: &[isize],
+        rule: &ReportRule,
+        max_removals: usize,
+    ) -> Result<(), String> {
+        let fast: Option<Vec<usize>> = find_removals(report, rule, max_removals);
+        let slow: Option<Vec<usize>> = brute_force_removals(report, rule, max_removals);
+        let agrees: bool = match (&fast, &slow) {
+            (Some(fast), Some(slow)) => {
+                fast.len() == slow.len()
+                    && fast.iter().all(|idx| *idx < report.len())
+                    && fast.windows(2).all(|pair| pair[0] < pair[1])
+                    && rule.is_safe(&without(report, fast))
+            }
+            (None, None) => true,
+            _ => false,
+        };
+
+        if agrees {
+            Ok(())
+        } else {
+            Err(format!(
+                "{:?} with up to {} removals: found {:?}, but brute force found {:?}",
+                report, max_removals, fast, slow
+            ))
+        }
+    }
+
+    // generates pseudo-random reports for checking against the oracle, using a
+    // fixed seed so failures are reproducible
+    fn random_reports(n_reports: usize, seed: u64) -> Vec<Vec<isize>> {
+        let mut rng = XorShift::new(seed);
+        let mut next = move |bound: u64| rng.below(bound);
+
+        (0..n_reports)
+            .map(|_| {
+                // mostly small steps, so plenty of reports are nearly safe
+                let len: u64 = next(9);
+                let mut level: isize = next(20) as isize;
+                (0..len)
+                    .map(|_| {
+                        level += next(11) as isize - 5;
+                        level
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn removes_the_first_level() {
+        let rule: ReportRule = puzzle_rule();
+        assert_eq!(find_removals(&[9, 1, 2, 3, 4], &rule, 1), Some(vec![0]));
+        assert_eq!(find_removals(&[1, 5, 4, 3, 2], &rule, 1), Some(vec![0]));
+    }
+
+    #[test]
+    fn removes_the_later_level_of_a_bad_pair() {
+        let rule: ReportRule = puzzle_rule();
+        assert_eq!(find_removals(&[1, 2, 3, 9, 4], &rule, 1), Some(vec![3]));
+        assert_eq!(find_removals(&[1, 2, 7, 3, 4], &rule, 1), Some(vec![2]));
+        assert_eq!(find_removals(&[1, 2, 3, 4, 9], &rule, 1), Some(vec![4]));
+        assert_eq!(
+            find_removals(&[5, 1, 2, 9, 3, 4], &rule, 2),
+            Some(vec![0, 3])
+        );
+    }
+
+    #[test]
+    fn gives_up_past_max_removals() {
+        let rule: ReportRule = puzzle_rule();
+        assert_eq!(find_removals(&[1, 2, 3, 4], &rule, 0), Some(Vec::new()));
+        assert_eq!(find_removals(&[5, 1, 2, 9, 3, 4], &rule, 1), None);
+        assert_eq!(find_removals(&[1, 5, 9, 13], &rule, 1), None);
+    }
+
+    #[test]
+    fn agrees_with_brute_force_on_random_reports() {
+        let rules: [ReportRule; 2] = [
+            puzzle_rule(),
+            ReportRule::new(Direction::Increasing)
+                .with(FollowsOrder)
+                .with(StepRange { min: 0, max: None }),
+        ];
+        for rule in &rules {
+            for report in random_reports(20_000, 0x2024_1202) {
+                for k in 0..=2 {
+                    if let Err(msg) = check_against_oracle(&report, rule, k) {
+                        panic!("dampener disagrees with brute force: {}", msg);
+                    }
+                }
+            }
+        }
+    }
+}
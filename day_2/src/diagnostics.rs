@@ -0,0 +1,334 @@
+/* file:    diagnostics.rs
+ * author:  garnt
+ * date:    12/21/2024
+ * desc:    Per-report explanations of why each day 2 report is or isn't safe.
+ */
+
+use crate::dampener;
+use crate::report_rule::{ReportRule, Violation};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+/// whether a report is safe, and why
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// the report is safe as-is
+    Safe,
+    /// the report is safe once the levels at these indices are removed
+    SafeWithRemoval(Vec<usize>),
+    /// the report can't be made safe. pair is the index of the first pair of
+    /// levels that breaks the rule, in whichever direction got furthest.
+    Unsafe {
+        pair: usize,
+        levels: (isize, isize),
+        reason: Violation,
+    },
+}
+
+/// a single report, along with its verdict
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// the 1-based line number the report was on
+    pub line_no: usize,
+    pub report: Vec<isize>,
+    pub verdict: Verdict,
+}
+
+/// the formats diagnoses can be printed in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DiagnosticsFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+impl FromStr for DiagnosticsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(DiagnosticsFormat::Table),
+            "csv" => Ok(DiagnosticsFormat::Csv),
+            "json" => Ok(DiagnosticsFormat::Json),
+            _ => Err(format!(
+                "unknown diagnostics format '{}', expected table, csv or json",
+                s
+            )),
+        }
+    }
+}
+
+/// works out why a report is or isn't safe, allowing up to max_removals
+/// levels to be removed
+pub fn diagnose(report: &[isize], rule: &ReportRule, max_removals: usize) -> Verdict {
+    match dampener::find_removals(report, rule, max_removals) {
+        Some(removed) if removed.is_empty() => Verdict::Safe,
+        Some(removed) => Verdict::SafeWithRemoval(removed),
+        None => {
+            // blame the first bad pair in whichever direction got furthest,
+            // since that's the one the report was most likely meant to follow
+            let (order, pair): (Ordering, usize) = rule
+                .orders()
+                .iter()
+                .map(|order| (*order, rule.first_violation(report, *order).unwrap()))
+                .max_by_key(|(_, pair)| *pair)
+                .unwrap();
+            let levels: (isize, isize) = (report[pair], report[pair + 1]);
+            Verdict::Unsafe {
+                pair,
+                levels,
+                reason: rule.violation(levels.0, levels.1, order).unwrap(),
+            }
+        }
+    }
+}
+
+// the verdict's columns: status, removed indices, pair index, levels, reason
+fn verdict_columns(verdict: &Verdict) -> [String; 5] {
+    let join = |vals: &[usize]| {
+        vals.iter()
+            .map(|val| val.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+    match verdict {
+        Verdict::Safe => [
+            "safe".into(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ],
+        Verdict::SafeWithRemoval(removed) => [
+            "safe_with_removal".into(),
+            join(removed),
+            String::new(),
+            String::new(),
+            String::new(),
+        ],
+        Verdict::Unsafe {
+            pair,
+            levels,
+            reason,
+        } => [
+            "unsafe".into(),
+            String::new(),
+            pair.to_string(),
+            format!("{} {}", levels.0, levels.1),
+            reason.name().into(),
+        ],
+    }
+}
+
+// formats a list of numbers as a json array
+fn json_array<T: ToString>(vals: &[T]) -> String {
+    let items: Vec<String> = vals.iter().map(|val| val.to_string()).collect();
+    format!("[{}]", items.join(","))
+}
+
+// formats a single diagnosis as a json object
+fn json_object(diagnosis: &Diagnosis) -> String {
+    let verdict_fields: String = match &diagnosis.verdict {
+        Verdict::Safe => String::from("\"status\":\"safe\""),
+        Verdict::SafeWithRemoval(removed) => format!(
+            "\"status\":\"safe_with_removal\",\"removed\":{}",
+            json_array(removed)
+        ),
+        Verdict::Unsafe {
+            pair,
+            levels,
+            reason,
+        } => format!(
+            "\"status\":\"unsafe\",\"pair\":{},\"levels\":[{},{}],\"reason\":\"{}\"",
+            pair,
+            levels.0,
+            levels.1,
+            reason.name()
+        ),
+    };
+    format!(
+        "{{\"line\":{},\"report\":{},{}}}",
+        diagnosis.line_no,
+        json_array(&diagnosis.report),
+        verdict_fields
+    )
+}
+
+/// renders every diagnosis in the provided format
+pub fn render_diagnoses(diagnoses: &[Diagnosis], format: DiagnosticsFormat) -> String {
+    const HEADERS: [&str; 7] = [
+        "line", "report", "status", "removed", "pair", "levels", "reason",
+    ];
+    let rows: Vec<[String; 7]> = diagnoses
+        .iter()
+        .map(|diagnosis| {
+            let [status, removed, pair, levels, reason] = verdict_columns(&diagnosis.verdict);
+            let report: Vec<String> = diagnosis.report.iter().map(|l| l.to_string()).collect();
+            [
+                diagnosis.line_no.to_string(),
+                report.join(" "),
+                status,
+                removed,
+                pair,
+                levels,
+                reason,
+            ]
+        })
+        .collect();
+
+    match format {
+        DiagnosticsFormat::Table => {
+            // pad every column to its widest value
+            let widths: Vec<usize> = (0..HEADERS.len())
+                .map(|col| {
+                    rows.iter()
+                        .map(|row| row[col].len())
+                        .chain(std::iter::once(HEADERS[col].len()))
+                        .max()
+                        .unwrap()
+                })
+                .collect();
+            let render_row = |cells: Vec<&str>| {
+                let padded: Vec<String> = cells
+                    .iter()
+                    .zip(widths.iter())
+                    .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                    .collect();
+                format!("{}\n", padded.join(" | ").trim_end())
+            };
+
+            let mut rendered: String = render_row(HEADERS.to_vec());
+            for row in &rows {
+                rendered.push_str(&render_row(row.iter().map(String::as_str).collect()));
+            }
+            rendered
+        }
+        DiagnosticsFormat::Csv => {
+            // none of the values contain commas or quotes, so nothing needs
+            // escaping
+            let mut rendered: String = format!("{}\n", HEADERS.join(","));
+            for row in &rows {
+                rendered.push_str(&format!("{}\n", row.join(",")));
+            }
+            rendered
+        }
+        DiagnosticsFormat::Json => {
+            let objects: Vec<String> = diagnoses.iter().map(json_object).collect();
+            format!("[\n  {}\n]\n", objects.join(",\n  "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report_rule::{Direction, FollowsOrder, StepRange};
+
+    // the rule from the puzzle: strictly increasing or decreasing, in steps of
+    // 1-3
+    fn puzzle_rule() -> ReportRule {
+        ReportRule::new(Direction::Either)
+            .with(FollowsOrder)
+            .with(StepRange::PUZZLE)
+    }
+
+    // the first few reports from the puzzle's example
+    fn example_diagnoses() -> Vec<Diagnosis> {
+        let rule: ReportRule = puzzle_rule();
+        [
+            vec![7, 6, 4, 2, 1],
+            vec![1, 2, 7, 8, 9],
+            vec![1, 3, 2, 4, 5],
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, report)| Diagnosis {
+            line_no: idx + 1,
+            verdict: diagnose(&report, &rule, 1),
+            report,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn verdicts() {
+        let rule: ReportRule = puzzle_rule();
+        let unsafe_verdict =
+            |pair: usize, levels: (isize, isize), reason: Violation| Verdict::Unsafe {
+                pair,
+                levels,
+                reason,
+            };
+        let cases: [(&[isize], usize, Verdict); 8] = [
+            (&[7, 6, 4, 2, 1], 1, Verdict::Safe),
+            (&[1, 3, 2, 4, 5], 1, Verdict::SafeWithRemoval(vec![1])),
+            (&[8, 6, 4, 4, 1], 1, Verdict::SafeWithRemoval(vec![2])),
+            (
+                &[8, 6, 4, 4, 1],
+                0,
+                unsafe_verdict(2, (4, 4), Violation::NoChange),
+            ),
+            // the decreasing direction gets further, so it's the one blamed
+            (
+                &[9, 7, 6, 2, 1],
+                1,
+                unsafe_verdict(2, (6, 2), Violation::StepTooLarge),
+            ),
+            (
+                &[1, 2, 7, 8, 9],
+                1,
+                unsafe_verdict(1, (2, 7), Violation::StepTooLarge),
+            ),
+            (
+                &[1, 2, 1, 2, 1],
+                1,
+                unsafe_verdict(1, (2, 1), Violation::WrongDirection),
+            ),
+            (
+                &[1, 1, 1, 1],
+                1,
+                unsafe_verdict(0, (1, 1), Violation::NoChange),
+            ),
+        ];
+        for (report, max_removals, expected) in cases {
+            assert_eq!(
+                diagnose(report, &rule, max_removals),
+                expected,
+                "{:?}",
+                report
+            );
+        }
+    }
+
+    #[test]
+    fn renderings() {
+        let diagnoses: Vec<Diagnosis> = example_diagnoses();
+        assert_eq!(
+            render_diagnoses(&diagnoses, DiagnosticsFormat::Table),
+            "\
+line | report    | status            | removed | pair | levels | reason
+1    | 7 6 4 2 1 | safe              |         |      |        |
+2    | 1 2 7 8 9 | unsafe            |         | 1    | 2 7    | step_too_large
+3    | 1 3 2 4 5 | safe_with_removal | 1       |      |        |
+"
+        );
+        assert_eq!(
+            render_diagnoses(&diagnoses, DiagnosticsFormat::Csv),
+            "\
+line,report,status,removed,pair,levels,reason
+1,7 6 4 2 1,safe,,,,
+2,1 2 7 8 9,unsafe,,1,2 7,step_too_large
+3,1 3 2 4 5,safe_with_removal,1,,,
+"
+        );
+        assert_eq!(
+            render_diagnoses(&diagnoses, DiagnosticsFormat::Json),
+            r#"[
+  {"line":1,"report":[7,6,4,2,1],"status":"safe"},
+  {"line":2,"report":[1,2,7,8,9],"status":"unsafe","pair":1,"levels":[2,7],"reason":"step_too_large"},
+  {"line":3,"report":[1,3,2,4,5],"status":"safe_with_removal","removed":[1]}
+]
+"#
+        );
+    }
+}
@@ -4,97 +4,119 @@
  * desc:    Advent of Code 2024 day 2.
  */
 
-use std::cmp::Ordering;
+mod dampener;
+mod diagnostics;
+mod report_rule;
 
-fn slice_is_sorted<T>(slice: &[T]) -> bool
-where
-    T: Ord,
-{
-    slice.windows(2).all(|w| w[0] <= w[1]) || slice.windows(2).all(|w| w[0] >= w[1])
-}
+use aoc_common::input::{ArgError, Args, DayInputs, InputSource};
+use diagnostics::{Diagnosis, DiagnosticsFormat};
+use report_rule::{Direction, FollowsOrder, ReportRule, StepRange};
 
-fn nums_in_range(a: isize, b: isize) -> bool {
-    let abs_diff: usize = a.abs_diff(b);
-    abs_diff > 0 && abs_diff < 4
+/// parses a single space-separated report
+fn parse_report(line: &str) -> Vec<isize> {
+    line.split(' ')
+        .map(|num_str| num_str.parse::<isize>().unwrap())
+        .collect()
 }
 
-fn process_line(line: &str) -> bool {
-    let nums_vec: Vec<isize> = line
-        .split(' ')
-        .map(|num_str| num_str.parse::<isize>().unwrap())
-        .collect();
+fn process_line(line: &str, rule: &ReportRule) -> bool {
+    rule.is_safe(&parse_report(line))
+}
 
-    slice_is_sorted(nums_vec.as_slice())
-        && nums_vec
-            .windows(2)
-            .all(|pair| nums_in_range(pair[0], pair[1]))
+fn process_line_with_removal(line: &str, rule: &ReportRule, max_removals: usize) -> bool {
+    let nums_vec: Vec<isize> = parse_report(line);
+    dampener::find_removals(&nums_vec, rule, max_removals).is_some()
 }
 
-fn slice_is_sorted_ranged_skip(slice: &[isize], order: Ordering) -> bool {
-    fn do_cmp(a: isize, b: isize, order: Ordering) -> bool {
-        if order == Ordering::Less {
-            return a.cmp(&b).is_le();
-        } else {
-            return a.cmp(&b).is_ge();
+/// parses a step range, either "min..=max" or "min.." for no upper limit
+fn parse_steps(steps_str: &str) -> Result<(usize, Option<usize>), String> {
+    let parse_step = |step_str: &str| {
+        step_str
+            .parse::<usize>()
+            .map_err(|e| format!("invalid step '{}': {}", step_str, e))
+    };
+    match steps_str.split_once("..") {
+        Some((min, max)) => {
+            let max: &str = max.trim_start_matches('=');
+            let max_step: Option<usize> = match max {
+                "" => None,
+                _ => Some(parse_step(max)?),
+            };
+            Ok((parse_step(min)?, max_step))
         }
+        None => Err(String::from("expected a range, e.g. 1..=3")),
     }
+}
 
-    let mut has_skipped: bool = false;
-    let mut skipped_value: Option<isize> = None;
-
-    // check if sorted least-to-greatest
-    for pair in slice.windows(2) {
-        if let Some(skipped) = skipped_value {
-            if do_cmp(skipped, pair[1], order) && nums_in_range(skipped, pair[1]) {
-                // lookin good
-                skipped_value = None;
-            } else {
-                return false;
-            }
-        } else {
-            if do_cmp(pair[0], pair[1], order) && nums_in_range(pair[0], pair[1]) {
-                // everything lookin good
-                continue;
-            } else if !has_skipped {
-                has_skipped = true;
-                skipped_value = Some(pair[0]);
-            } else {
-                return false;
+fn main() {
+    // parse the optional arguments, e.g. `day_2 --steps 1..=5 --direction up`
+    // or `day_2 --removals 2` or `day_2 --diagnostics csv`
+    let mut direction: Direction = Direction::Either;
+    let mut steps: StepRange = StepRange::PUZZLE;
+    let mut max_removals: usize = 1;
+    let mut diagnostics_format: Option<DiagnosticsFormat> = None;
+    let mut args = Args::from_env();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--steps" => (steps.min, steps.max) = args.value_with(&arg, parse_steps),
+            "--direction" => direction = args.value(&arg),
+            "--removals" => max_removals = args.value(&arg),
+            "--diagnostics" => {
+                diagnostics_format = Some(
+                    args.optional_value(&arg)
+                        .unwrap_or(DiagnosticsFormat::Table),
+                )
             }
+            _ => ArgError::Unknown(arg).exit(),
         }
     }
-    return true;
-}
-
-fn process_line_with_removal(line: &str) -> bool {
-    let nums_vec: Vec<isize> = line
-        .split(' ')
-        .map(|num_str| num_str.parse::<isize>().unwrap())
-        .collect();
+    let source: InputSource = args.source().clone();
 
-    let is_lt = slice_is_sorted_ranged_skip(nums_vec.as_slice(), Ordering::Less);
-    let is_gt = slice_is_sorted_ranged_skip(nums_vec.as_slice(), Ordering::Greater);
+    // build the rule from the arguments. by default it's the puzzle's,
+    // strictly increasing or decreasing in steps of 1-3, and e.g.
+    // `--steps 0..=3` allows levels to stay the same.
+    let rule: ReportRule = ReportRule::new(direction).with(FollowsOrder).with(steps);
 
-    println!("{:?} | {} - {}", nums_vec, is_lt, is_gt);
-
-    // return our results
-    is_lt || is_gt
-}
-
-fn main() {
     // metadata
-    let input: String = std::fs::read_to_string("input.txt").unwrap();
+    let inputs = DayInputs::new(2, env!("CARGO_MANIFEST_DIR"));
+    let input: String = inputs.load_or_exit(&source);
     println!("n_lines: {}", input.lines().count());
+    if direction != Direction::Either || steps != StepRange::PUZZLE {
+        println!("rule: {:?}", rule);
+    }
 
     // part 1
-    let n_safe: usize = input.lines().map(process_line).filter(|b| *b).count();
+    let n_safe: usize = input
+        .lines()
+        .map(|line| process_line(line, &rule))
+        .filter(|b| *b)
+        .count();
     println!("part 1: {}", n_safe);
+    inputs.report_answer(&source, 1, &n_safe);
 
     // part 2
     let n_safe_2: usize = input
         .lines()
-        .map(process_line_with_removal)
+        .map(|line| process_line_with_removal(line, &rule, max_removals))
         .filter(|b| *b)
         .count();
     println!("part 2: {}", n_safe_2);
+    inputs.report_answer(&source, 2, &n_safe_2);
+
+    // optionally explain why each report is or isn't safe
+    if let Some(format) = diagnostics_format {
+        let diagnoses: Vec<Diagnosis> = input
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| {
+                let report: Vec<isize> = parse_report(line);
+                Diagnosis {
+                    line_no: idx + 1,
+                    verdict: diagnostics::diagnose(&report, &rule, max_removals),
+                    report,
+                }
+            })
+            .collect();
+        print!("{}", diagnostics::render_diagnoses(&diagnoses, format));
+    }
 }
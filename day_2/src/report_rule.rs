@@ -0,0 +1,173 @@
+/* file:    report_rule.rs
+ * author:  garnt
+ * date:    12/21/2024
+ * desc:    Configurable rules deciding whether a day 2 report is safe.
+ */
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// the direction every level in a report has to move in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+    /// either increasing or decreasing, as long as the whole report agrees
+    Either,
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "increasing" | "up" => Ok(Direction::Increasing),
+            "decreasing" | "down" => Ok(Direction::Decreasing),
+            "either" => Ok(Direction::Either),
+            _ => Err(format!(
+                "unknown direction '{}', expected increasing, decreasing or either",
+                s
+            )),
+        }
+    }
+}
+
+/// why an adjacent pair of levels breaks a rule
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// the levels move against the report's direction
+    WrongDirection,
+    /// the levels are equal, but have to change by at least some step
+    NoChange,
+    /// the levels change by less than the smallest allowed step
+    StepTooSmall,
+    /// the levels change by more than the largest allowed step
+    StepTooLarge,
+}
+
+impl Violation {
+    /// a short, machine-friendly name for the violation
+    pub fn name(&self) -> &'static str {
+        match self {
+            Violation::WrongDirection => "wrong_direction",
+            Violation::NoChange => "no_change",
+            Violation::StepTooSmall => "step_too_small",
+            Violation::StepTooLarge => "step_too_large",
+        }
+    }
+}
+
+/// a condition every adjacent pair of levels in a safe report has to meet.
+/// new tolerance rules implement this, rather than changing ReportRule.
+pub trait PairCheck: fmt::Debug {
+    /// returns why a followed by b isn't allowed in a report following order,
+    /// if it isn't
+    fn check(&self, a: isize, b: isize, order: Ordering) -> Option<Violation>;
+}
+
+/// levels never move against the report's order, though they may stay level
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FollowsOrder;
+
+impl PairCheck for FollowsOrder {
+    fn check(&self, a: isize, b: isize, order: Ordering) -> Option<Violation> {
+        (a.cmp(&b) == order.reverse()).then_some(Violation::WrongDirection)
+    }
+}
+
+/// adjacent levels differ by at least min and, if it's set, at most max.
+/// equal levels are only allowed if min is 0.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StepRange {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl StepRange {
+    /// the steps allowed by the puzzle, 1-3
+    pub const PUZZLE: Self = Self {
+        min: 1,
+        max: Some(3),
+    };
+}
+
+impl PairCheck for StepRange {
+    fn check(&self, a: isize, b: isize, _order: Ordering) -> Option<Violation> {
+        let step: usize = a.abs_diff(b);
+        if step < self.min {
+            Some(if step == 0 {
+                Violation::NoChange
+            } else {
+                Violation::StepTooSmall
+            })
+        } else if self.max.is_some_and(|max| step > max) {
+            Some(Violation::StepTooLarge)
+        } else {
+            None
+        }
+    }
+}
+
+/// decides whether a report is safe by running every check on every adjacent
+/// pair of levels, in one of the directions the report may move in
+#[derive(Debug)]
+pub struct ReportRule {
+    pub direction: Direction,
+    pub checks: Vec<Box<dyn PairCheck>>,
+}
+
+impl ReportRule {
+    /// constructs a new ReportRule with no checks, so any report in one of
+    /// direction's orders is safe until checks are added
+    pub fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            checks: Vec::new(),
+        }
+    }
+
+    /// adds a check that every adjacent pair has to pass
+    pub fn with(mut self, check: impl PairCheck + 'static) -> Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// returns the orderings adjacent pairs may have, one of which the whole
+    /// report has to follow
+    pub fn orders(&self) -> &'static [Ordering] {
+        match self.direction {
+            Direction::Increasing => &[Ordering::Less],
+            Direction::Decreasing => &[Ordering::Greater],
+            Direction::Either => &[Ordering::Less, Ordering::Greater],
+        }
+    }
+
+    /// returns why a followed by b isn't allowed in a report following order,
+    /// if it isn't, from the first check that fails
+    pub fn violation(&self, a: isize, b: isize, order: Ordering) -> Option<Violation> {
+        self.checks
+            .iter()
+            .find_map(|check| check.check(a, b, order))
+    }
+
+    /// returns true if a followed by b is allowed in a report following order
+    pub fn pair_ok(&self, a: isize, b: isize, order: Ordering) -> bool {
+        self.violation(a, b, order).is_none()
+    }
+
+    /// returns the index of the first pair that breaks the rule in a report
+    /// following order, if there is one
+    pub fn first_violation(&self, report: &[isize], order: Ordering) -> Option<usize> {
+        report
+            .windows(2)
+            .position(|pair| !self.pair_ok(pair[0], pair[1], order))
+    }
+
+    /// returns true if the whole report follows the rule
+    pub fn is_safe(&self, report: &[isize]) -> bool {
+        self.orders()
+            .iter()
+            .any(|order| self.first_violation(report, *order).is_none())
+    }
+}
@@ -0,0 +1,264 @@
+/* file:    interpreter.rs
+ * author:  garnt
+ * date:    12/21/2024
+ * desc:    Interpreter for the instructions found in day 3's corrupted memory.
+ */
+
+use crate::lexer::{Instruction, InstructionSpec, Lexer, OperandSyntax, Token};
+use malachite_nz::integer::Integer;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+
+/// the state of the machine as instructions are run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Machine {
+    /// whether instructions that change the total currently take effect
+    pub enabled: bool,
+    /// the running total, which can grow as large as it needs to
+    pub total: Integer,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            total: Integer::from(0),
+        }
+    }
+}
+
+/// runs a custom instruction against the machine, given its arguments
+pub type Handler = fn(&mut Machine, &[Integer]);
+
+/// adds the sum of the arguments to the total, if enabled
+pub fn add(machine: &mut Machine, args: &[Integer]) {
+    if machine.enabled {
+        machine.total += args.iter().sum::<Integer>();
+    }
+}
+
+/// adds the first argument minus the rest to the total, if enabled
+pub fn sub(machine: &mut Machine, args: &[Integer]) {
+    if let (true, Some((first, rest))) = (machine.enabled, args.split_first()) {
+        machine.total += first - rest.iter().sum::<Integer>();
+    }
+}
+
+/// adds the bitwise xor of the arguments to the total, if enabled
+pub fn xor(machine: &mut Machine, args: &[Integer]) {
+    if machine.enabled {
+        machine.total += args.iter().fold(Integer::from(0), |acc, arg| acc ^ arg);
+    }
+}
+
+/// why an instruction couldn't be run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StepError {
+    /// the interpreter doesn't know the instruction
+    Unknown(InstructionSpec),
+    /// a custom instruction has a different number of arguments from the one
+    /// its handler was registered with
+    WrongArity {
+        name: &'static str,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepError::Unknown(spec) => {
+                write!(f, "unknown instruction {}/{}", spec.name, spec.arity)
+            }
+            StepError::WrongArity {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}() takes {} arguments, but was given {}",
+                name, expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StepError {}
+
+/// a set of instructions, the puzzle's own and any custom ones along with the
+/// handler to run for each of them
+#[derive(Clone, Debug, Default)]
+pub struct Interpreter {
+    specs: Vec<InstructionSpec>,
+    syntax: OperandSyntax,
+    handlers: HashMap<&'static str, (usize, Handler)>,
+}
+
+impl Interpreter {
+    /// constructs a new Interpreter that doesn't know any instructions
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// teaches the interpreter one of the puzzle's own instructions, like
+    /// InstructionSpec::MUL
+    pub fn with_builtin(mut self, spec: InstructionSpec) -> Self {
+        assert!(
+            InstructionSpec::BUILTIN.contains(&spec),
+            "{:?} isn't a built-in instruction",
+            spec
+        );
+        if !self.specs.contains(&spec) {
+            self.specs.push(spec);
+        }
+        self
+    }
+
+    /// registers a handler for a custom name(arg,...) instruction with exactly
+    /// arity arguments, replacing any handler already registered under that
+    /// name. the puzzle's own instructions can't be replaced.
+    pub fn with(mut self, name: &'static str, arity: usize, handler: Handler) -> Self {
+        assert!(
+            InstructionSpec::BUILTIN
+                .iter()
+                .all(|builtin| builtin.name != name),
+            "{}() is a built-in instruction",
+            name
+        );
+        self.specs.retain(|spec| spec.name != name);
+        self.specs.push(InstructionSpec { name, arity });
+        self.handlers.insert(name, (arity, handler));
+        self
+    }
+
+    /// makes every instruction's operands follow the provided syntax, rather
+    /// than the puzzle's own
+    pub fn with_syntax(mut self, syntax: OperandSyntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+
+    /// the interpreter for part 1, which only knows mul()
+    pub fn part_1() -> Self {
+        Self::new().with_builtin(InstructionSpec::MUL)
+    }
+
+    /// the interpreter for part 2, which also knows do() and don't()
+    pub fn part_2() -> Self {
+        Self::part_1()
+            .with_builtin(InstructionSpec::DO)
+            .with_builtin(InstructionSpec::DONT)
+    }
+
+    /// the interpreter for part 2, plus add(), sub() and xor(), which all
+    /// take two arguments and change the total like mul() does
+    pub fn extended() -> Self {
+        Self::part_2()
+            .with("add", 2, add)
+            .with("sub", 2, sub)
+            .with("xor", 2, xor)
+    }
+
+    /// returns true if the interpreter has an instruction by this one's name
+    pub fn knows(&self, instruction: &Instruction) -> bool {
+        let name: &str = instruction.spec().name;
+        self.specs.iter().any(|spec| spec.name == name)
+    }
+
+    /// returns a Lexer that yields this interpreter's instructions from reader
+    pub fn lexer<R: Read>(&self, reader: R) -> Lexer<R> {
+        Lexer::new(reader, self.specs.clone(), self.syntax)
+    }
+
+    /// runs a single instruction against the machine
+    pub fn step(&self, machine: &mut Machine, instruction: &Instruction) -> Result<(), StepError> {
+        if !self.knows(instruction) {
+            return Err(StepError::Unknown(instruction.spec()));
+        }
+
+        match instruction {
+            Instruction::Mul(a, b) => {
+                if machine.enabled {
+                    machine.total += a * b;
+                }
+            }
+            Instruction::Do => machine.enabled = true,
+            Instruction::Dont => machine.enabled = false,
+            Instruction::Custom(name, args) => {
+                let (arity, handler) = self.handlers[name];
+                if args.len() != arity {
+                    return Err(StepError::WrongArity {
+                        name,
+                        expected: arity,
+                        found: args.len(),
+                    });
+                }
+                handler(machine, args);
+            }
+        }
+        Ok(())
+    }
+
+    /// runs every token in order on a fresh machine, returning its final state
+    pub fn run<'a>(
+        &self,
+        tokens: impl IntoIterator<Item = &'a Token>,
+    ) -> Result<Machine, StepError> {
+        let mut machine = Machine::default();
+        for token in tokens {
+            self.step(&mut machine, &token.instruction)?;
+        }
+        Ok(machine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // lexes and runs the input with the provided interpreter
+    fn run(interpreter: &Interpreter, input: &str) -> Result<Machine, StepError> {
+        let tokens: Vec<Token> = interpreter
+            .lexer(input.as_bytes())
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        interpreter.run(&tokens)
+    }
+
+    #[test]
+    fn dispatches_builtin_and_custom_instructions() {
+        let input: &str = "mul(2,3)add(4,5)don't()sub(9,1)do()xor(6,3)sub(1,4)";
+        assert_eq!(run(&Interpreter::part_2(), input).unwrap().total, 6);
+        assert_eq!(run(&Interpreter::extended(), input).unwrap().total, 17);
+    }
+
+    #[test]
+    fn rejects_unknown_instructions_and_wrong_arities() {
+        let mut machine = Machine::default();
+        let unknown = Instruction::Custom("add", vec![Integer::from(1), Integer::from(2)]);
+        assert_eq!(
+            Interpreter::part_2().step(&mut machine, &unknown),
+            Err(StepError::Unknown(InstructionSpec {
+                name: "add",
+                arity: 2
+            }))
+        );
+        assert_eq!(
+            Interpreter::part_1().step(&mut machine, &Instruction::Do),
+            Err(StepError::Unknown(InstructionSpec::DO))
+        );
+
+        let one_arg = Instruction::Custom("add", vec![Integer::from(1)]);
+        assert_eq!(
+            Interpreter::extended().step(&mut machine, &one_arg),
+            Err(StepError::WrongArity {
+                name: "add",
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(machine.total, 0);
+    }
+}
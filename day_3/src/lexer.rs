@@ -0,0 +1,482 @@
+/* file:    lexer.rs
+ * author:  garnt
+ * date:    12/21/2024
+ * desc:    Streaming lexer for day 3's corrupted memory.
+ */
+
+use malachite_nz::integer::Integer;
+use malachite_nz::natural::Natural;
+use std::collections::VecDeque;
+use std::io::{BufReader, Bytes, Read};
+
+/// describes which numbers are accepted as an instruction's operands
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OperandSyntax {
+    /// the fewest digits an operand can have, not counting any sign or prefix
+    pub min_digits: usize,
+    /// the most digits an operand can have, not counting any sign or prefix
+    pub max_digits: usize,
+    /// whether operands may start with a '+' or '-'
+    pub allow_sign: bool,
+    /// whether operands may be written in hex, with a "0x" prefix
+    pub allow_hex: bool,
+}
+
+impl Default for OperandSyntax {
+    /// the puzzle's own syntax, 1-3 unsigned decimal digits
+    fn default() -> Self {
+        Self {
+            min_digits: 1,
+            max_digits: 3,
+            allow_sign: false,
+            allow_hex: false,
+        }
+    }
+}
+
+impl OperandSyntax {
+    /// the longest an operand can be, in bytes
+    fn max_len(&self) -> usize {
+        let sign_len: usize = usize::from(self.allow_sign);
+        let prefix_len: usize = if self.allow_hex { 2 } else { 0 };
+        sign_len + prefix_len + self.max_digits
+    }
+
+    // consumes an operand from the front of the slice, returning the number
+    // and how many bytes it took up
+    fn match_operand(&self, slice: &[u8]) -> Option<(Integer, usize)> {
+        // an optional sign
+        let mut len: usize = 0;
+        let negative: bool = self.allow_sign && slice.first() == Some(&b'-');
+        if self.allow_sign && matches!(slice.first(), Some(b'+' | b'-')) {
+            len += 1;
+        }
+
+        // an optional hex prefix, as long as it's followed by a hex digit
+        let mut radix: u32 = 10;
+        if self.allow_hex
+            && matches!(slice.get(len..len + 2), Some(b"0x" | b"0X"))
+            && slice
+                .get(len + 2)
+                .is_some_and(|byte| byte.is_ascii_hexdigit())
+        {
+            radix = 16;
+            len += 2;
+        }
+
+        // and then the digits themselves
+        let n_digits: usize = slice[len..]
+            .iter()
+            .take(self.max_digits)
+            .take_while(|byte| (**byte as char).is_digit(radix))
+            .count();
+        if n_digits == 0 || n_digits < self.min_digits {
+            return None;
+        }
+
+        let magnitude: Natural =
+            slice[len..len + n_digits]
+                .iter()
+                .fold(Natural::from(0u32), |acc, digit| {
+                    (acc * Natural::from(radix))
+                        + Natural::from((*digit as char).to_digit(radix).unwrap())
+                });
+        let num: Integer = if negative {
+            -Integer::from(magnitude)
+        } else {
+            Integer::from(magnitude)
+        };
+        Some((num, len + n_digits))
+    }
+}
+
+/// describes an instruction the lexer should look for, written as
+/// name(arg,arg,...) with exactly arity arguments
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InstructionSpec {
+    pub name: &'static str,
+    pub arity: usize,
+}
+
+impl InstructionSpec {
+    /// mul(a,b), which multiplies its arguments
+    pub const MUL: Self = Self {
+        name: "mul",
+        arity: 2,
+    };
+    /// do(), which enables the instructions that follow
+    pub const DO: Self = Self {
+        name: "do",
+        arity: 0,
+    };
+    /// don't(), which disables the instructions that follow
+    pub const DONT: Self = Self {
+        name: "don't",
+        arity: 0,
+    };
+    /// the instructions from the puzzle itself
+    pub const BUILTIN: [Self; 3] = [Self::MUL, Self::DO, Self::DONT];
+
+    /// the longest this instruction can be, in bytes, if its operands follow
+    /// the provided syntax
+    fn max_len(&self, syntax: &OperandSyntax) -> usize {
+        let commas: usize = self.arity.saturating_sub(1);
+        self.name.len() + 2 + (self.arity * syntax.max_len()) + commas
+    }
+}
+
+/// a single valid instruction found in the corrupted memory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(Integer, Integer),
+    Do,
+    Dont,
+    /// any other instruction, by name, along with its arguments
+    Custom(&'static str, Vec<Integer>),
+}
+
+impl Instruction {
+    /// builds the instruction described by spec from its arguments, which
+    /// there must be spec.arity of
+    fn from_spec(spec: &InstructionSpec, mut args: Vec<Integer>) -> Self {
+        match *spec {
+            InstructionSpec::MUL => {
+                let b: Integer = args.pop().unwrap();
+                let a: Integer = args.pop().unwrap();
+                Instruction::Mul(a, b)
+            }
+            InstructionSpec::DO => Instruction::Do,
+            InstructionSpec::DONT => Instruction::Dont,
+            _ => Instruction::Custom(spec.name, args),
+        }
+    }
+
+    /// the spec this instruction was lexed from
+    pub fn spec(&self) -> InstructionSpec {
+        match self {
+            Instruction::Mul(..) => InstructionSpec::MUL,
+            Instruction::Do => InstructionSpec::DO,
+            Instruction::Dont => InstructionSpec::DONT,
+            Instruction::Custom(name, args) => InstructionSpec {
+                name,
+                arity: args.len(),
+            },
+        }
+    }
+}
+
+/// an instruction, along with the byte offset it starts at
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub offset: usize,
+    pub len: usize,
+    pub instruction: Instruction,
+}
+
+/// yields every valid instruction in a stream of corrupted memory, in a single
+/// pass, only ever buffering enough bytes to match the longest instruction
+pub struct Lexer<R: Read> {
+    specs: Vec<InstructionSpec>,
+    syntax: OperandSyntax,
+    max_token_len: usize,
+    bytes: Bytes<BufReader<R>>,
+    window: VecDeque<u8>,
+    offset: usize,
+}
+
+impl<R: Read> Lexer<R> {
+    /// constructs a new Lexer that looks for the provided instructions, with
+    /// operands written in the provided syntax
+    pub fn new(reader: R, specs: Vec<InstructionSpec>, syntax: OperandSyntax) -> Self {
+        let max_token_len: usize = specs
+            .iter()
+            .map(|spec| spec.max_len(&syntax))
+            .max()
+            .unwrap_or(1);
+        Self {
+            specs,
+            syntax,
+            max_token_len,
+            bytes: BufReader::new(reader).bytes(),
+            window: VecDeque::with_capacity(max_token_len),
+            offset: 0,
+        }
+    }
+
+    // tops the window back up to max_token_len bytes, unless the stream ends
+    fn fill_window(&mut self) -> std::io::Result<()> {
+        while self.window.len() < self.max_token_len {
+            match self.bytes.next() {
+                Some(byte) => self.window.push_back(byte?),
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    // drops n bytes from the front of the window
+    fn advance(&mut self, n: usize) {
+        self.window.drain(..n);
+        self.offset += n;
+    }
+}
+
+/// returns the instruction described by spec at the very start of the slice
+/// and its length in bytes, if there is one
+fn match_instruction(
+    slice: &[u8],
+    spec: &InstructionSpec,
+    syntax: &OperandSyntax,
+) -> Option<(Instruction, usize)> {
+    let name: &[u8] = spec.name.as_bytes();
+    if !slice.starts_with(name) || slice.get(name.len()) != Some(&b'(') {
+        return None;
+    }
+
+    // name(a,b,...), where each argument follows the operand syntax
+    let mut len: usize = name.len() + 1;
+    let mut args: Vec<Integer> = Vec::with_capacity(spec.arity);
+    for arg_idx in 0..spec.arity {
+        if arg_idx > 0 {
+            if slice.get(len) != Some(&b',') {
+                return None;
+            }
+            len += 1;
+        }
+        let (arg, arg_len) = syntax.match_operand(&slice[len..])?;
+        args.push(arg);
+        len += arg_len;
+    }
+    if slice.get(len) != Some(&b')') {
+        return None;
+    }
+
+    Some((Instruction::from_spec(spec, args), len + 1))
+}
+
+impl<R: Read> Iterator for Lexer<R> {
+    type Item = std::io::Result<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Err(e) = self.fill_window() {
+                return Some(Err(e));
+            }
+            if self.window.is_empty() {
+                return None;
+            }
+
+            // try to match an instruction at the current offset, and skip a
+            // single byte if there isn't one
+            let window: &[u8] = self.window.make_contiguous();
+            let matched = self
+                .specs
+                .iter()
+                .find_map(|spec| match_instruction(window, spec, &self.syntax));
+            match matched {
+                Some((instruction, len)) => {
+                    let token = Token {
+                        offset: self.offset,
+                        len,
+                        instruction,
+                    };
+                    self.advance(len);
+                    return Some(Ok(token));
+                }
+                None => self.advance(1),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the puzzle's part 2 example
+    const EXAMPLE: &str =
+        "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+    // hands out at most chunk_len bytes per read, to split instructions
+    // across reads
+    struct ChunkedReader<'a> {
+        bytes: &'a [u8],
+        chunk_len: usize,
+    }
+
+    impl Read for ChunkedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len: usize = self.chunk_len.min(buf.len()).min(self.bytes.len());
+            buf[..len].copy_from_slice(&self.bytes[..len]);
+            self.bytes = &self.bytes[len..];
+            Ok(len)
+        }
+    }
+
+    // lexes the builtin instructions from a reader
+    fn lex_from<R: Read>(reader: R, syntax: OperandSyntax) -> Vec<Token> {
+        Lexer::new(reader, InstructionSpec::BUILTIN.to_vec(), syntax)
+            .collect::<std::io::Result<_>>()
+            .unwrap()
+    }
+
+    // lexes the builtin instructions from a string
+    fn lex(input: &str, syntax: OperandSyntax) -> Vec<Token> {
+        lex_from(input.as_bytes(), syntax)
+    }
+
+    // builds a mul instruction
+    fn mul(a: i64, b: i64) -> Instruction {
+        Instruction::Mul(Integer::from(a), Integer::from(b))
+    }
+
+    #[test]
+    fn tokens_know_where_they_are() {
+        let spans: Vec<(usize, usize, Instruction)> = lex(EXAMPLE, OperandSyntax::default())
+            .into_iter()
+            .map(|token| (token.offset, token.len, token.instruction))
+            .collect();
+        assert_eq!(
+            spans,
+            [
+                (1, 8, mul(2, 4)),
+                (20, 7, Instruction::Dont),
+                (28, 8, mul(5, 5)),
+                (48, 9, mul(11, 8)),
+                (59, 4, Instruction::Do),
+                (64, 8, mul(8, 5)),
+            ]
+        );
+        for (offset, len, instruction) in spans {
+            let text: &str = &EXAMPLE[offset..offset + len];
+            assert!(text.starts_with(instruction.spec().name), "{}", text);
+        }
+    }
+
+    #[test]
+    fn instructions_split_across_reads_are_found() {
+        // long enough to span several of BufReader's buffers, too
+        let input: String = EXAMPLE.repeat(200);
+        let expected: Vec<Token> = lex(&input, OperandSyntax::default());
+        assert_eq!(expected.len(), 6 * 200);
+        for chunk_len in [1, 2, 5, 13] {
+            let reader = ChunkedReader {
+                bytes: input.as_bytes(),
+                chunk_len,
+            };
+            assert_eq!(
+                lex_from(reader, OperandSyntax::default()),
+                expected,
+                "chunk_len {}",
+                chunk_len
+            );
+        }
+    }
+
+    #[test]
+    fn puzzle_syntax_rejects_malformed_muls() {
+        for input in [
+            "mul(1234,5)",
+            "mul( 1,2)",
+            "mul(1 ,2)",
+            "mul(1,2",
+            "mul(,2)",
+            "mul(1,2,3)",
+            "mul(-1,2)",
+            "mul(0x1,2)",
+            "MUL(1,2)",
+            "do( )",
+        ] {
+            assert_eq!(lex(input, OperandSyntax::default()), [], "{}", input);
+        }
+
+        // a rejected instruction doesn't hide a valid one inside it
+        let tokens: Vec<Token> = lex("mul(1,mul(2,3)", OperandSyntax::default());
+        assert_eq!(tokens.len(), 1);
+        assert_eq!((tokens[0].offset, &tokens[0].instruction), (6, &mul(2, 3)));
+    }
+
+    #[test]
+    fn signs_and_hex_are_opt_in() {
+        let input: &str = "mul(-0x1F,+3)mul(0xff,-12)";
+        let signed_hex = OperandSyntax {
+            allow_sign: true,
+            allow_hex: true,
+            ..OperandSyntax::default()
+        };
+        let instructions = |syntax: OperandSyntax| -> Vec<Instruction> {
+            lex(input, syntax)
+                .into_iter()
+                .map(|token| token.instruction)
+                .collect()
+        };
+        assert_eq!(instructions(signed_hex), [mul(-31, 3), mul(255, -12)]);
+
+        // the hex prefix doesn't count towards the digits
+        let one_digit = OperandSyntax {
+            max_digits: 2,
+            ..signed_hex
+        };
+        assert_eq!(instructions(one_digit), [mul(-31, 3), mul(255, -12)]);
+
+        // either option alone rejects both
+        for syntax in [
+            OperandSyntax::default(),
+            OperandSyntax {
+                allow_sign: true,
+                ..OperandSyntax::default()
+            },
+            OperandSyntax {
+                allow_hex: true,
+                ..OperandSyntax::default()
+            },
+        ] {
+            assert_eq!(instructions(syntax), [], "{:?}", syntax);
+        }
+    }
+
+    #[test]
+    fn hex_prefixes_need_a_hex_digit() {
+        let hex = OperandSyntax {
+            allow_hex: true,
+            ..OperandSyntax::default()
+        };
+        for input in ["mul(0x,2)", "mul(0xg,2)", "mul(2,0x)", "mul(0x-1,2)"] {
+            assert_eq!(lex(input, hex), [], "{}", input);
+        }
+        let tokens: Vec<Token> = lex("mul(0X0,0)mul(0xA,10)", hex);
+        let instructions: Vec<&Instruction> =
+            tokens.iter().map(|token| &token.instruction).collect();
+        assert_eq!(instructions, [&mul(0, 0), &mul(10, 10)]);
+    }
+
+    #[test]
+    fn digit_counts_are_bounded() {
+        let two_to_four = OperandSyntax {
+            min_digits: 2,
+            max_digits: 4,
+            ..OperandSyntax::default()
+        };
+        let accepted = |input: &str| !lex(input, two_to_four).is_empty();
+        assert!(accepted("mul(10,9999)"));
+        assert!(accepted("mul(0001,00)"));
+        assert!(!accepted("mul(1,22)"));
+        assert!(!accepted("mul(22,1)"));
+        assert!(!accepted("mul(22,55555)"));
+
+        // a bigger limit needs a bigger window, which the lexer sizes itself
+        let wide = OperandSyntax {
+            max_digits: 30,
+            ..OperandSyntax::default()
+        };
+        let tokens: Vec<Token> = lex("mul(123456789012345678901234567890,2)", wide);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(
+            tokens[0].instruction,
+            Instruction::Mul(
+                "123456789012345678901234567890".parse().unwrap(),
+                Integer::from(2)
+            )
+        );
+    }
+}
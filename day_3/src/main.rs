@@ -4,49 +4,144 @@
  * desc:    Advent of Code 2024 day 2.
  */
 
-use regex::Regex;
-
-fn visit_mul_do_dont(acc: (bool, usize), cap_str: &str) -> (bool, usize) {
-    let mul_re = Regex::new(r"mul\((\d{1,3})\,(\d{1,3})\)").unwrap();
-    match cap_str {
-        "do()" => (true, acc.1),
-        "don't()" => (false, acc.1),
-        _ => {
-            if acc.0 {
-                let mul_caps = mul_re.captures_iter(cap_str).next().unwrap();
-                let [a_str, b_str] = mul_caps.extract::<2>().1;
-                let product = a_str.parse::<usize>().unwrap() * b_str.parse::<usize>().unwrap();
-                (acc.0, acc.1 + product)
-            } else {
-                acc
-            }
+mod interpreter;
+mod lexer;
+mod trace;
+
+use aoc_common::input::{ArgError, Args, DayInputs, InputSource};
+use interpreter::Interpreter;
+use lexer::{OperandSyntax, Token};
+use malachite_nz::integer::Integer;
+use std::io::Read;
+use trace::{Step, TraceFormat};
+
+/// parses a digit count range, either "min..=max" or a single exact count
+fn parse_digits(digits_str: &str) -> Result<(usize, usize), String> {
+    let parse_count = |count_str: &str| {
+        count_str
+            .parse::<usize>()
+            .map_err(|e| format!("invalid digit count '{}': {}", count_str, e))
+    };
+    let (min_digits, max_digits) = match digits_str.split_once("..=") {
+        Some((min, max)) => (parse_count(min)?, parse_count(max)?),
+        None => {
+            let n_digits: usize = parse_count(digits_str)?;
+            (n_digits, n_digits)
         }
+    };
+
+    // either of these would never match anything
+    if max_digits == 0 {
+        return Err(String::from("operands need at least 1 digit"));
+    }
+    if min_digits > max_digits {
+        return Err(format!(
+            "the minimum of {} digits is more than the maximum of {}",
+            min_digits, max_digits
+        ));
     }
+    Ok((min_digits, max_digits))
 }
 
 fn main() {
-    // construct mul regex, mul/do/dont regex
-    let mul_re = Regex::new(r"mul\((\d{1,3})\,(\d{1,3})\)").unwrap();
-    let mul_do_dont_re = Regex::new(r"(mul\(\d{1,3}\,\d{1,3}\))|(do\(\))|(don't\(\))").unwrap();
+    // parse the optional arguments, e.g. `day_3 --trace html > trace.html` or
+    // `day_3 --digits 1..=5 --signed --hex --extended --input example`
+    let mut trace_format: Option<TraceFormat> = None;
+    let mut syntax: OperandSyntax = OperandSyntax::default();
+    let mut extended: bool = false;
+    let mut args = Args::from_env();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--trace" => {
+                trace_format = Some(args.optional_value(&arg).unwrap_or(TraceFormat::Ansi))
+            }
+            "--digits" => {
+                (syntax.min_digits, syntax.max_digits) = args.value_with(&arg, parse_digits)
+            }
+            "--signed" => syntax.allow_sign = true,
+            "--hex" => syntax.allow_hex = true,
+            "--extended" => extended = true,
+            _ => ArgError::Unknown(arg).exit(),
+        }
+    }
+    let source: InputSource = args.source().clone();
+    if syntax != OperandSyntax::default() {
+        println!("operand syntax: {:?}", syntax);
+    }
 
-    // metadata
-    let input: String = std::fs::read_to_string("input.txt").unwrap();
+    // lex the input as it's read in, rather than loading it all at once,
+    // unless the trace needs all of it anyway. part 2's instructions are a
+    // superset of part 1's, and the extended set is a superset of part 2's, so
+    // every interpreter can share the same tokens.
+    let inputs = DayInputs::new(3, env!("CARGO_MANIFEST_DIR"));
+    let part_1 = Interpreter::part_1().with_syntax(syntax);
+    let part_2 = Interpreter::part_2().with_syntax(syntax);
+    let extended: Option<Interpreter> =
+        extended.then(|| Interpreter::extended().with_syntax(syntax));
+    let raw_input: Option<String> = trace_format.map(|_| inputs.load_or_exit(&source));
+    let reader: Box<dyn Read + '_> = match &raw_input {
+        Some(input) => Box::new(input.as_bytes()),
+        None => inputs.open(&source).unwrap_or_else(|e| {
+            eprintln!("failed to load input - {}", e);
+            std::process::exit(1);
+        }),
+    };
+    let tokens: Vec<Token> = extended
+        .as_ref()
+        .unwrap_or(&part_2)
+        .lexer(reader)
+        .collect::<std::io::Result<Vec<Token>>>()
+        .unwrap();
 
-    // part 1
-    let mul_sum: usize = mul_re
-        .captures_iter(&input)
-        .map(|c| c.extract())
-        .map(|(_, [a, b])| a.parse::<usize>().unwrap() * b.parse::<usize>().unwrap())
-        .sum();
-    println!("part one: {:?}", &mul_sum);
+    // part 1, which skips the instructions only part 2 knows
+    let part_1_tokens = tokens
+        .iter()
+        .filter(|token| part_1.knows(&token.instruction));
+    let mul_sum: Integer = part_1.run(part_1_tokens).unwrap().total;
+    println!("part one: {}", &mul_sum);
+    inputs.report_answer(&source, 1, &mul_sum);
 
     // part 2
-    let mul_sum_with_do_dont: usize = mul_do_dont_re
-        .captures_iter(&input)
-        .map(|c| c.extract().1)
-        .fold((true, 0 as usize), |acc, c: [&str; 1]| {
-            visit_mul_do_dont(acc, c[0])
-        })
-        .1;
-    println!("part one: {:?}", &mul_sum_with_do_dont);
+    let part_2_tokens = tokens
+        .iter()
+        .filter(|token| part_2.knows(&token.instruction));
+    let mul_sum_with_do_dont: Integer = part_2.run(part_2_tokens).unwrap().total;
+    println!("part two: {}", &mul_sum_with_do_dont);
+    inputs.report_answer(&source, 2, &mul_sum_with_do_dont);
+
+    // optionally run the extended instruction set too
+    if let Some(extended) = &extended {
+        println!("extended: {}", extended.run(&tokens).unwrap().total);
+    }
+
+    // optionally show which instructions contributed to the total, with the
+    // extended instruction set if it was asked for
+    if let (Some(format), Some(input)) = (trace_format, &raw_input) {
+        let tracer: &Interpreter = extended.as_ref().unwrap_or(&part_2);
+        let steps: Vec<Step> = trace::trace_steps(tracer, &tokens).unwrap();
+        print!("{}", trace::render_trace(input, &steps, format));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digit_ranges_must_match_something() {
+        assert_eq!(parse_digits("4"), Ok((4, 4)));
+        assert_eq!(parse_digits("0..=5"), Ok((0, 5)));
+        assert_eq!(parse_digits("2..=2"), Ok((2, 2)));
+        assert_eq!(
+            parse_digits("5..=1"),
+            Err(String::from(
+                "the minimum of 5 digits is more than the maximum of 1"
+            ))
+        );
+        assert_eq!(
+            parse_digits("0"),
+            Err(String::from("operands need at least 1 digit"))
+        );
+        assert!(parse_digits("1..5").is_err());
+    }
 }
@@ -0,0 +1,149 @@
+/* file:    trace.rs
+ * author:  garnt
+ * date:    12/21/2024
+ * desc:    Annotated traces of day 3's corrupted memory, showing which
+ *          instructions contributed to the total.
+ */
+
+use crate::interpreter::{Interpreter, Machine, StepError};
+use crate::lexer::{Instruction, Token};
+use malachite_nz::integer::Integer;
+use std::str::FromStr;
+
+/// how a single instruction affected the machine
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StepKind {
+    /// the instruction ran while the machine was enabled
+    Active,
+    /// the instruction was skipped because the machine was disabled
+    Ignored,
+    /// the instruction enables or disables the machine
+    Toggle,
+}
+
+/// a single instruction, along with how it affected the machine and the
+/// running total once it had run
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    pub token: Token,
+    pub kind: StepKind,
+    pub total: Integer,
+}
+
+/// the formats a trace can be rendered in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    Ansi,
+    Html,
+}
+
+impl FromStr for TraceFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(TraceFormat::Ansi),
+            "html" => Ok(TraceFormat::Html),
+            _ => Err(format!(
+                "unknown trace format '{}', expected ansi or html",
+                s
+            )),
+        }
+    }
+}
+
+/// runs every token in order on a fresh machine, recording how each one
+/// affected it
+pub fn trace_steps(interpreter: &Interpreter, tokens: &[Token]) -> Result<Vec<Step>, StepError> {
+    let mut machine = Machine::default();
+    tokens
+        .iter()
+        .map(|token| {
+            let was_enabled: bool = machine.enabled;
+            interpreter.step(&mut machine, &token.instruction)?;
+            let kind: StepKind = if was_enabled != machine.enabled
+                || matches!(token.instruction, Instruction::Do | Instruction::Dont)
+            {
+                StepKind::Toggle
+            } else if was_enabled {
+                StepKind::Active
+            } else {
+                StepKind::Ignored
+            };
+            Ok(Step {
+                token: token.clone(),
+                kind,
+                total: machine.total.clone(),
+            })
+        })
+        .collect()
+}
+
+// escapes the characters html gives special meaning to
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// renders a single instruction, followed by the running total if it ran
+fn render_step(step: &Step, text: &str, format: TraceFormat) -> String {
+    match (format, step.kind) {
+        (TraceFormat::Ansi, StepKind::Active) => {
+            format!("\x1b[1;32m{}\x1b[0m\x1b[36m[={}]\x1b[0m", text, step.total)
+        }
+        (TraceFormat::Ansi, StepKind::Ignored) => format!("\x1b[2;9m{}\x1b[0m", text),
+        (TraceFormat::Ansi, StepKind::Toggle) => format!("\x1b[1;33;7m{}\x1b[0m", text),
+        (TraceFormat::Html, StepKind::Active) => format!(
+            "<span class=\"active\">{}</span><span class=\"total\">[={}]</span>",
+            escape_html(text),
+            step.total
+        ),
+        (TraceFormat::Html, StepKind::Ignored) => {
+            format!("<span class=\"ignored\">{}</span>", escape_html(text))
+        }
+        (TraceFormat::Html, StepKind::Toggle) => {
+            format!("<span class=\"toggle\">{}</span>", escape_html(text))
+        }
+    }
+}
+
+/// renders the input with every step's instruction annotated in place. the
+/// steps must have been lexed from the same input.
+pub fn render_trace(input: &str, steps: &[Step], format: TraceFormat) -> String {
+    let mut rendered: String = String::new();
+    if format == TraceFormat::Html {
+        rendered.push_str(concat!(
+            "<!DOCTYPE html>\n<html><head><style>\n",
+            "pre { white-space: pre-wrap; word-break: break-all; color: #888; }\n",
+            ".active { color: #0a0; font-weight: bold; }\n",
+            ".ignored { color: #ccc; text-decoration: line-through; }\n",
+            ".toggle { background: #fd0; color: #000; font-weight: bold; }\n",
+            ".total { color: #08c; font-size: smaller; }\n",
+            "</style></head><body><pre>"
+        ));
+    }
+
+    // copy the corrupted memory between instructions as-is, and annotate the
+    // instructions themselves
+    let mut cursor: usize = 0;
+    for step in steps {
+        let (start, end) = (step.token.offset, step.token.offset + step.token.len);
+        let between: &str = &input[cursor..start];
+        match format {
+            TraceFormat::Ansi => rendered.push_str(between),
+            TraceFormat::Html => rendered.push_str(&escape_html(between)),
+        }
+        rendered.push_str(&render_step(step, &input[start..end], format));
+        cursor = end;
+    }
+    match format {
+        TraceFormat::Ansi => rendered.push_str(&input[cursor..]),
+        TraceFormat::Html => {
+            rendered.push_str(&escape_html(&input[cursor..]));
+            rendered.push_str("</pre></body></html>\n");
+        }
+    }
+
+    rendered
+}
@@ -4,6 +4,8 @@
  * desc:    Advent of Code 2024 day 4.
  */
 
+use aoc_common::input::{DayInputs, InputSource};
+
 #[derive(Clone, Copy, Debug)]
 enum DirectionSteps {
     HorizLeft,
@@ -208,8 +210,10 @@ fn find_smartass(
 }
 
 fn main() {
-    // metadata
-    let input: String = std::fs::read_to_string("input.txt").unwrap();
+    // metadata, e.g. `day_4 --input example`
+    let source: InputSource = InputSource::from_env_args();
+    let inputs = DayInputs::new(4, env!("CARGO_MANIFEST_DIR"));
+    let input: String = inputs.load_or_exit(&source);
     println!(
         "width: {} - height: {}",
         input.lines().next().unwrap().len(),
@@ -225,6 +229,7 @@ fn main() {
         }
     }
     println!("Part 1: {}", found_count);
+    inputs.report_answer(&source, 1, &found_count);
 
     // part 2
     found_count = 0;
@@ -236,4 +241,5 @@ fn main() {
         }
     }
     println!("Part 2: {}", found_count);
+    inputs.report_answer(&source, 2, &found_count);
 }
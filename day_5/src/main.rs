@@ -4,6 +4,7 @@
  * desc:    Advent of Code 2024 day 5.
  */
 
+use aoc_common::input::{DayInputs, InputSource};
 use petgraph::{csr::DefaultIx, graph::NodeIndex, stable_graph::StableGraph};
 use std::collections::HashMap;
 use std::hash::Hash;
@@ -71,7 +72,10 @@ where
 }
 
 fn main() {
-    let input: String = std::fs::read_to_string("input.txt").unwrap();
+    // read the input, e.g. `day_5 --input example`
+    let source: InputSource = InputSource::from_env_args();
+    let inputs = DayInputs::new(5, env!("CARGO_MANIFEST_DIR"));
+    let input: String = inputs.load_or_exit(&source);
 
     // populate the edges and sets from the input
     let mut edges: Vec<(usize, usize)> = Vec::new();
@@ -122,5 +126,7 @@ fn main() {
     }
 
     println!("Part 1: {}", correct_sum);
+    inputs.report_answer(&source, 1, &correct_sum);
     println!("Part 2: {}", incorrect_sum);
+    inputs.report_answer(&source, 2, &incorrect_sum);
 }
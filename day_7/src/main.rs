@@ -4,6 +4,7 @@
  * desc:    Advent of Code 2024 day 7.
  */
 
+use aoc_common::input::{DayInputs, InputSource};
 use indicatif::ProgressIterator;
 use malachite_nz::natural::Natural;
 
@@ -91,8 +92,10 @@ fn try_find_arith_path_with_concat(
 }
 
 fn main() {
-    // metadata
-    let input: String = std::fs::read_to_string("input.txt").unwrap();
+    // metadata, e.g. `day_7 --input example`
+    let source: InputSource = InputSource::from_env_args();
+    let inputs = DayInputs::new(7, env!("CARGO_MANIFEST_DIR"));
+    let input: String = inputs.load_or_exit(&source);
     println!("n_lines: {}", input.lines().count());
 
     // parse the text into tuples of (usize, Vec<usize>)
@@ -116,6 +119,7 @@ fn main() {
         }
     }
     println!("Part 1: {}", workable_sum);
+    inputs.report_answer(&source, 1, &workable_sum);
 
     // part 2
     workable_sum = Natural::default();
@@ -125,4 +129,5 @@ fn main() {
         }
     }
     println!("Part 2: {}", workable_sum);
+    inputs.report_answer(&source, 2, &workable_sum);
 }
@@ -4,13 +4,8 @@
  * desc:    Advent of Code 2024 day 8.
  */
 
-use itertools::Itertools;
-use std::collections::HashMap;
-
-// returns true if a, b, and c are colinear
-fn are_points_colinear(a: (isize, isize), b: (isize, isize), c: (isize, isize)) -> bool {
-    (c.0 - b.0) * (b.1 - a.1) == (b.0 - a.0) * (c.1 - b.1)
-}
+use aoc_common::input::{ArgError, Args, DayInputs, InputName, InputSource};
+use aoc_common::spatial_index::{Coord, SpatialIndex};
 
 // returns true if both coordinates are within the range [min, max)
 fn point_in_range<T>(coord: (T, T), min: T, max: T) -> bool
@@ -20,92 +15,368 @@ where
     coord.0 >= min && coord.0 < max && coord.1 >= min && coord.1 < max
 }
 
-fn main() {
-    // metadata
-    let input: String = std::fs::read_to_string("input.txt").unwrap();
-    let width: usize = input.lines().next().unwrap().len();
-    let height: usize = input.lines().count();
-    println!("width: {} - height: {}", width, height);
-    assert_eq!(width, height);
+// returns the greatest common divisor of a and b, always non-negative
+fn gcd(a: isize, b: isize) -> isize {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
 
-    // search for every tower and make a map of their locations
-    let mut name_coord_map: HashMap<char, Vec<(isize, isize)>> = HashMap::new();
-    for line in input.lines().enumerate() {
-        for cur_char in line.1.chars().enumerate() {
-            // ignore empty slots
-            if cur_char.1 == '.' {
-                continue;
-            }
+/// which multiples k of the tower-to-tower vector produce an antinode at
+/// point_a + k * (point_b - point_a)
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Harmonics {
+    /// only the listed multiples, e.g. [-1, 2] for "one step beyond each tower"
+    Multiples(Vec<isize>),
+    /// every multiple in the inclusive range [min, max]
+    Bounded(isize, isize),
+    /// every multiple that lands on the map
+    All,
+}
 
-            if name_coord_map.contains_key(&cur_char.1) {
-                // if there's already an entry in the HashMap, add to it
-                name_coord_map
-                    .get_mut(&cur_char.1)
-                    .unwrap()
-                    .push((cur_char.0 as isize, line.0 as isize));
-            } else {
-                // otherwise, make a new vec and add it to the HashMap
-                let new_pair_vec: Vec<(isize, isize)> =
-                    vec![(cur_char.0 as isize, line.0 as isize)];
-                name_coord_map.insert(cur_char.1, new_pair_vec);
-            }
+impl std::str::FromStr for Harmonics {
+    type Err = String;
+
+    /// parses "all", an inclusive range like "0..=5", or a comma-separated list
+    /// of multiples like "-1,2"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_int = |num_str: &str| {
+            num_str
+                .trim()
+                .parse::<isize>()
+                .map_err(|e| format!("invalid multiple '{}': {}", num_str, e))
+        };
+
+        if s == "all" {
+            Ok(Harmonics::All)
+        } else if let Some((min_str, max_str)) = s.split_once("..=") {
+            Ok(Harmonics::Bounded(parse_int(min_str)?, parse_int(max_str)?))
+        } else {
+            Ok(Harmonics::Multiples(
+                s.split(',').map(parse_int).collect::<Result<_, _>>()?,
+            ))
         }
     }
+}
 
-    // generate a list containing all the possible pairings of coordinates of
-    // two matching towers
-    let mut tower_lines: Vec<(char, [(isize, isize); 2])> = Vec::new();
-    for (name, coord_pairs) in name_coord_map.iter() {
-        for combo in coord_pairs.iter().combinations(2) {
-            let a: [(isize, isize); 2] = combo
-                .iter()
-                .map(|&pair| (pair.0, pair.1))
-                .collect::<Vec<(isize, isize)>>()
-                .try_into()
-                .unwrap();
-            tower_lines.push((*name, a));
+/// describes where a pair of matching towers creates antinodes
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct AntinodeRule {
+    harmonics: Harmonics,
+    /// if set, the tower-to-tower vector is divided by its gcd first, so every
+    /// colinear grid point is reachable by an integer multiple
+    reduce_vector: bool,
+}
+
+impl AntinodeRule {
+    /// the rule from part 1: one step beyond each tower
+    fn part_1() -> Self {
+        Self {
+            harmonics: Harmonics::Multiples(vec![-1, 2]),
+            reduce_vector: false,
         }
     }
 
-    // for each point pair, calculate where its antinodes could exist, and mark
-    // the bitflag
-    let mut has_antinode: Vec<bool> = [false].repeat(width * height);
-    for (_, [point_a, point_b]) in tower_lines.iter() {
-        let ab_vec = (point_b.0 - point_a.0, point_b.1 - point_a.1);
-        let ba_vec = (point_a.0 - point_b.0, point_a.1 - point_b.1);
-        let ab_antinode = (point_b.0 + ab_vec.0, point_b.1 + ab_vec.1);
-        let ba_antinode = (point_a.0 + ba_vec.0, point_a.1 + ba_vec.1);
-        if point_in_range(ab_antinode, 0, width as isize) {
-            let flag_coord: usize = (ab_antinode.1 as usize * width) + ab_antinode.0 as usize;
-            *has_antinode.get_mut(flag_coord).unwrap() = true;
+    /// the rule from part 2: every point in line with both towers
+    fn part_2() -> Self {
+        Self {
+            harmonics: Harmonics::All,
+            reduce_vector: true,
+        }
+    }
+
+    /// returns every on-map antinode created by the provided pair of towers
+    fn antinodes(&self, point_a: Coord, point_b: Coord, size: isize) -> Vec<Coord> {
+        let mut step = (point_b.0 - point_a.0, point_b.1 - point_a.1);
+        if self.reduce_vector {
+            let divisor = gcd(step.0, step.1).max(1);
+            step = (step.0 / divisor, step.1 / divisor);
+        }
+        let point_at = |k: isize| (point_a.0 + k * step.0, point_a.1 + k * step.1);
+
+        match &self.harmonics {
+            Harmonics::Multiples(multiples) => multiples
+                .iter()
+                .map(|k| point_at(*k))
+                .filter(|point| point_in_range(*point, 0, size))
+                .collect(),
+            Harmonics::Bounded(min, max) => (*min..=*max)
+                .map(point_at)
+                .filter(|point| point_in_range(*point, 0, size))
+                .collect(),
+            Harmonics::All => {
+                // a zero step would walk forever, and only ever hits point_a
+                if step == (0, 0) {
+                    return vec![point_a];
+                }
+
+                // walk outwards from point_a in both directions until we leave
+                // the map
+                let forwards = (0..)
+                    .map(point_at)
+                    .take_while(|point| point_in_range(*point, 0, size));
+                let backwards = (1..)
+                    .map(|k| point_at(-k))
+                    .take_while(|point| point_in_range(*point, 0, size));
+                forwards.chain(backwards).collect()
+            }
         }
+    }
+}
 
-        if point_in_range(ba_antinode, 0, width as isize) {
-            let flag_coord: usize = (ba_antinode.1 as usize * width) + ba_antinode.0 as usize;
-            *has_antinode.get_mut(flag_coord).unwrap() = true;
+/// returns a flag for each map position (row-major) holding the frequency of
+/// the last pair of towers to place an antinode there, if any
+fn mark_antinodes(
+    tower_lines: &[(char, [Coord; 2])],
+    rule: &AntinodeRule,
+    width: usize,
+) -> Vec<Option<char>> {
+    let mut antinode_freqs: Vec<Option<char>> = [None].repeat(width * width);
+    for (name, [point_a, point_b]) in tower_lines.iter() {
+        for antinode in rule.antinodes(*point_a, *point_b, width as isize) {
+            let flag_coord: usize = (antinode.1 as usize * width) + antinode.0 as usize;
+            antinode_freqs[flag_coord] = Some(*name);
         }
     }
 
-    println!(
-        "Part 1: {}",
-        &has_antinode.iter().counts().get(&true).unwrap()
-    );
-
-    // for each point that could contain an antinode, check if it does
-    let mut n_antinodes: usize = 0;
-    for line in input.lines().enumerate() {
-        for cur_char in line.1.chars().enumerate() {
-            // for each pair of towers, check if the current point is colinear
-            for (_, [point_a, point_b]) in tower_lines.iter() {
-                if are_points_colinear((cur_char.0 as isize, line.0 as isize), *point_a, *point_b) {
-                    // there can only be one antinode at a point, so break after
-                    // incrementing the count
-                    n_antinodes += 1;
-                    break;
+    antinode_freqs
+}
+
+/// returns the number of distinct map positions holding at least one antinode
+fn count_antinodes(tower_lines: &[(char, [Coord; 2])], rule: &AntinodeRule, width: usize) -> usize {
+    mark_antinodes(tower_lines, rule, width)
+        .iter()
+        .filter(|flag| flag.is_some())
+        .count()
+}
+
+// returns the ANSI escape sequence used to colour the provided frequency
+fn freq_colour(name: char) -> String {
+    // skip black and white so every frequency stays readable
+    format!("\x1b[38;5;{}m", 1 + (name as u32 % 14))
+}
+
+/// renders the map in the puzzle's format, with towers drawn over the '#'
+/// antinode markers. towers are limited to a single frequency if only_freq is
+/// set, and everything is coloured per frequency if colour is set. antinode_freqs
+/// should already be limited to only_freq, since a cell only remembers one of
+/// the frequencies that marked it.
+fn render_map(
+    towers: &SpatialIndex<char>,
+    antinode_freqs: &[Option<char>],
+    width: usize,
+    only_freq: Option<char>,
+    colour: bool,
+) -> String {
+    // start with the antinodes, then draw the towers over them
+    let mut cells: Vec<Option<(char, char)>> = antinode_freqs
+        .iter()
+        .map(|flag| flag.map(|name| ('#', name)))
+        .collect();
+    let shown_towers: Vec<(char, Coord)> = match only_freq {
+        Some(only) => towers
+            .get(only)
+            .iter()
+            .map(|coord| (only, *coord))
+            .collect(),
+        None => towers.iter().collect(),
+    };
+    for (name, coord) in shown_towers {
+        cells[(coord.1 as usize * width) + coord.0 as usize] = Some((name, name));
+    }
+
+    let mut rendered = String::new();
+    for row in cells.chunks(width) {
+        for cell in row {
+            match cell {
+                Some((symbol, name)) if colour => {
+                    rendered.push_str(&format!("{}{}\x1b[0m", freq_colour(*name), symbol))
                 }
+                Some((symbol, _)) => rendered.push(*symbol),
+                None => rendered.push('.'),
             }
         }
+        rendered.push('\n');
     }
 
-    println!("Part 2: {}", n_antinodes);
+    rendered
+}
+
+// parses a comma-separated list of numbers as N_COORDS (x, y) pairs, e.g.
+// "1,2,3,4" -> [(1, 2), (3, 4)]
+fn parse_coords<const N_COORDS: usize>(arg: &str) -> Result<[Coord; N_COORDS], String> {
+    let nums: Vec<isize> = arg
+        .split(',')
+        .map(|num_str| {
+            num_str
+                .trim()
+                .parse::<isize>()
+                .map_err(|e| format!("invalid number '{}': {}", num_str, e))
+        })
+        .collect::<Result<_, _>>()?;
+    if nums.len() != 2 * N_COORDS {
+        return Err(format!(
+            "expected {} comma-separated numbers, found {}",
+            2 * N_COORDS,
+            nums.len()
+        ));
+    }
+
+    Ok(std::array::from_fn(|idx| {
+        (nums[2 * idx], nums[2 * idx + 1])
+    }))
+}
+
+fn main() {
+    // parse the optional arguments, e.g. `day_8 0..=3 reduce --render --only A`
+    // or `day_8 --input example --nearest 3,4`
+    let mut custom_rule: Option<AntinodeRule> = None;
+    let mut render: bool = false;
+    let mut colour: bool = false;
+    let mut only_freq: Option<char> = None;
+    let mut nearest_to: Option<Coord> = None;
+    let mut region: Option<[Coord; 2]> = None;
+    let mut args = Args::from_env();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--render" => render = true,
+            "--colour" | "--color" => colour = true,
+            "--only" => only_freq = Some(args.value(&arg)),
+            "--nearest" => nearest_to = Some(args.value_with(&arg, parse_coords::<1>)[0]),
+            "--region" => region = Some(args.value_with(&arg, parse_coords::<2>)),
+            // a custom rule is its harmonics, optionally followed by "reduce"
+            "reduce" if custom_rule.is_some() => {
+                custom_rule.as_mut().unwrap().reduce_vector = true;
+            }
+            _ if custom_rule.is_none() && !arg.starts_with("--") => {
+                let harmonics: Harmonics = arg.parse().unwrap_or_else(|reason| {
+                    ArgError::InvalidValue {
+                        flag: String::from("the custom rule"),
+                        value: arg.clone(),
+                        reason,
+                    }
+                    .exit()
+                });
+                custom_rule = Some(AntinodeRule {
+                    harmonics,
+                    reduce_vector: false,
+                });
+            }
+            _ => ArgError::Unknown(arg).exit(),
+        }
+    }
+
+    // metadata
+    let source: InputSource = args.source().clone();
+    let inputs = DayInputs::new(8, env!("CARGO_MANIFEST_DIR"))
+        .embed(InputName::Example(1), include_str!("../test-input.txt"));
+    let input: String = inputs.load_or_exit(&source);
+    let width: usize = input.lines().next().unwrap().len();
+    let height: usize = input.lines().count();
+    println!("width: {} - height: {}", width, height);
+    assert_eq!(width, height);
+
+    // search for every tower and make a map of their locations, ignoring
+    // empty slots
+    let towers: SpatialIndex<char> = SpatialIndex::from_grid(&input, |c| c == '.');
+
+    // generate a list containing all the possible pairings of coordinates of
+    // two matching towers
+    let tower_lines: Vec<(char, [Coord; 2])> = towers.pairs().collect();
+
+    for (part, rule) in [(1, AntinodeRule::part_1()), (2, AntinodeRule::part_2())] {
+        let n_antinodes: usize = count_antinodes(&tower_lines, &rule, width);
+        println!("Part {}: {}", part, n_antinodes);
+        inputs.report_answer(&source, part, &n_antinodes);
+    }
+
+    // optionally query the tower index, to help with debugging
+    if let Some(point) = nearest_to {
+        println!(
+            "Nearest tower to {:?}: {:?}",
+            point,
+            towers.nearest(point, only_freq)
+        );
+    }
+    if let Some([min, max]) = region {
+        let mut in_region: Vec<(char, Coord)> = towers
+            .in_region(min, max)
+            .into_iter()
+            .filter(|(name, _)| only_freq.is_none_or(|only| only == *name))
+            .collect();
+        in_region.sort_by_key(|(name, coord)| (coord.1, coord.0, *name));
+        println!("Towers in [{:?}, {:?}): {:?}", min, max, in_region);
+    }
+
+    // optionally evaluate a custom rule
+    let mut render_rule = AntinodeRule::part_2();
+    if let Some(custom_rule) = custom_rule {
+        println!(
+            "Custom {:?}: {}",
+            custom_rule,
+            count_antinodes(&tower_lines, &custom_rule, width)
+        );
+        render_rule = custom_rule;
+    }
+
+    // optionally draw the map with the antinodes from the last rule overlaid
+    if render {
+        // drop the other frequencies' towers before marking, so their
+        // antinodes can't overwrite the ones we want to see
+        let shown_lines: Vec<(char, [Coord; 2])> = tower_lines
+            .iter()
+            .filter(|(name, _)| only_freq.is_none_or(|only| only == *name))
+            .copied()
+            .collect();
+        let antinode_freqs = mark_antinodes(&shown_lines, &render_rule, width);
+        print!(
+            "{}",
+            render_map(&towers, &antinode_freqs, width, only_freq, colour)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // returns every pair of matching towers in the example, and its width
+    fn example_tower_lines() -> (Vec<(char, [Coord; 2])>, usize) {
+        let input: &str = include_str!("../test-input.txt");
+        let towers: SpatialIndex<char> = SpatialIndex::from_grid(input, |c| c == '.');
+        (towers.pairs().collect(), input.lines().count())
+    }
+
+    #[test]
+    fn example_input() {
+        let (tower_lines, width) = example_tower_lines();
+        assert_eq!(
+            count_antinodes(&tower_lines, &AntinodeRule::part_1(), width),
+            14
+        );
+        assert_eq!(
+            count_antinodes(&tower_lines, &AntinodeRule::part_2(), width),
+            34
+        );
+    }
+
+    #[test]
+    fn render_only_one_frequency() {
+        let input: &str = include_str!("../test-input.txt");
+        let towers: SpatialIndex<char> = SpatialIndex::from_grid(input, |c| c == '.');
+        let (tower_lines, width) = example_tower_lines();
+        // towers are drawn over the antinodes, so only the uncovered ones show
+        for (freq, expected) in [('A', 13), ('0', 17)] {
+            let freq_lines: Vec<(char, [Coord; 2])> = tower_lines
+                .iter()
+                .filter(|(name, _)| *name == freq)
+                .copied()
+                .collect();
+            let antinode_freqs = mark_antinodes(&freq_lines, &AntinodeRule::part_2(), width);
+            let rendered: String = render_map(&towers, &antinode_freqs, width, Some(freq), false);
+            assert_eq!(rendered.matches('#').count(), expected, "only {}", freq);
+        }
+    }
 }
@@ -4,6 +4,8 @@
  * desc:    Advent of Code 2024 day 9.
  */
 
+use aoc_common::input::{DayInputs, InputName, InputSource};
+
 /// Represents a single filesystem block
 #[derive(Clone, Copy, PartialEq, Debug)]
 struct Block {
@@ -173,8 +175,10 @@ fn defrag<'a>(blocks: &'a mut DblLinkedList<Block>) {
 
 /// the entrypoint
 fn main() {
-    // metadata
-    let input: String = std::fs::read_to_string("test-input.txt").unwrap();
+    // metadata, e.g. `day_9 --input example`
+    let input: String = DayInputs::new(9, env!("CARGO_MANIFEST_DIR"))
+        .embed(InputName::Example(1), include_str!("../test-input.txt"))
+        .load_or_exit(&InputSource::from_env_args());
     println!("n_blocks: {}", input.len());
 
     // initialize blocks